@@ -0,0 +1,55 @@
+use std::collections::BTreeMap;
+use std::process;
+use std::str::FromStr;
+
+// Command line: a command followed by positional arguments and `--key value`
+// (or `-k value`) options. Positional arguments may start by - (as signatures
+// written as +- strings do) as long as no letter follows the dashes.
+pub struct Args {
+    pub cmd: Option<String>,
+    pub rest: Vec<String>,
+    opts: BTreeMap<String, String>,
+}
+
+fn is_option(a: &str) -> bool {
+    return a.starts_with('-') && a.trim_start_matches('-').starts_with(|c: char| c.is_ascii_alphabetic());
+}
+
+impl Args {
+    pub fn parse(args: impl Iterator<Item = String>) -> Args {
+        let mut args = args.peekable();
+        let cmd = args.next_if(|a| !is_option(a));
+        let (mut rest, mut opts) = (Vec::new(), BTreeMap::new());
+        while let Some(a) = args.next() {
+            if !is_option(&a) { rest.push(a); continue; }
+            let key = a.trim_start_matches('-').to_string();
+            match args.next_if(|v| !is_option(v)) {
+                Some(v) => { opts.insert(key, v); }
+                None => die(&format!("missing value for {}", a)),
+            }
+        }
+        return Args { cmd, rest, opts };
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        return self.opts.get(key).map(|v| v.as_str());
+    }
+
+    // The value of the option key parsed as T, or default if it is not given.
+    pub fn num<T: FromStr>(&self, key: &str, default: T) -> T {
+        return match self.get(key) {
+            None => default,
+            Some(v) => v.parse().unwrap_or_else(|_| die(&format!("invalid value for --{}: {}", key, v))),
+        };
+    }
+
+    pub fn required(&self, key: &str) -> &str {
+        let dashes = if key.len() == 1 { "-" } else { "--" };
+        return self.get(key).unwrap_or_else(|| die(&format!("missing {}{}", dashes, key)));
+    }
+}
+
+pub fn die(msg: &str) -> ! {
+    eprintln!("he: {}", msg);
+    process::exit(2);
+}
@@ -0,0 +1,32 @@
+use std::io::{self, Read, Write};
+
+// Helpers for the binary table files. Everything is little endian.
+
+pub fn invalid(msg: &str) -> io::Error {
+    return io::Error::new(io::ErrorKind::InvalidData, msg);
+}
+
+pub fn write_u32(w: &mut impl Write, x: u32) -> io::Result<()> {
+    return w.write_all(&x.to_le_bytes());
+}
+
+pub fn write_u64(w: &mut impl Write, x: u64) -> io::Result<()> {
+    return w.write_all(&x.to_le_bytes());
+}
+
+pub fn read_u32(r: &mut impl Read) -> io::Result<u32> {
+    let mut x = [0; 4];
+    r.read_exact(&mut x)?;
+    return Ok(u32::from_le_bytes(x));
+}
+
+pub fn read_u64(r: &mut impl Read) -> io::Result<u64> {
+    let mut x = [0; 8];
+    r.read_exact(&mut x)?;
+    return Ok(u64::from_le_bytes(x));
+}
+
+// 64 bit FNV-1a hash, used as checksum.
+pub fn checksum(bytes: &[u8]) -> u64 {
+    return bytes.iter().fold(0xcbf29ce484222325, |h,b| (h ^ *b as u64).wrapping_mul(0x100000001b3));
+}
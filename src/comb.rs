@@ -0,0 +1,88 @@
+// Combinatorial indexing of the r-subsets of 1..n. Subsets {s_1 < .. < s_r}
+// are ranked in colex order, as the sum of C(s_i - 1, i), which does not depend
+// on n. For triples this is the cd used everywhere.
+
+pub fn binom(n: usize, r: usize) -> usize {
+    if r > n { return 0; }
+    return (0..r.min(n-r)).fold(1, |c,i| c * (n-i) / (i+1));
+}
+
+pub fn ntriples(n: usize) -> usize {
+    return binom(n, 3);
+}
+
+// Rank of the increasing subset s.
+pub fn rank(s: &[usize]) -> usize {
+    return s.iter().enumerate().map(|(i,x)| binom(x-1, i+1)).sum();
+}
+
+// The r-subset of rank x, increasing.
+pub fn unrank(mut x: usize, r: usize) -> Vec<usize> {
+    let mut s = vec![0; r];
+    for i in (1..=r).rev() {
+        let mut p = i;
+        while binom(p, i) <= x { p += 1; }
+        s[i-1] = p;
+        x -= binom(p-1, i);
+    }
+    return s;
+}
+
+// Practical bijection between {0,1,..,n(n-1)(n-2)/6 -1} and {(a,b,c) : 1 <= a < b < c <= n} forall n.
+// It is rank(&[a,b,c]), written out since it is computed in the inner loops.
+pub fn cd(a: usize, b: usize, c: usize) -> usize {
+    return (a-1) + (b-1)*(b-2)/2 + (c-1)*(c-2)*(c-3)/6;
+}
+
+pub fn uncd(t: usize) -> (usize,usize,usize) {
+    let s = unrank(t, 3);
+    return (s[0],s[1],s[2]);
+}
+
+// The r-subsets of 1..n in lexicographic order.
+pub struct Subsets {
+    n: usize,
+    cur: Vec<usize>,
+    done: bool,
+}
+
+pub fn subsets(n: usize, r: usize) -> Subsets {
+    return Subsets { n, cur: (1..=r).collect(), done: r > n };
+}
+
+impl Iterator for Subsets {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>> {
+        if self.done { return None; }
+        let s = self.cur.clone();
+        let r = self.cur.len();
+        match (0..r).rev().find(|i| self.cur[*i] < self.n - (r - 1 - i)) {
+            None => self.done = true,
+            Some(i) => {
+                self.cur[i] += 1;
+                for j in i+1..r { self.cur[j] = self.cur[j-1] + 1; }
+            }
+        }
+        return Some(s);
+    }
+}
+
+// The R-subsets of 1..n as arrays, in lexicographic order.
+pub fn tuples<const R: usize>(n: usize) -> impl Iterator<Item = [usize; R]> {
+    return subsets(n, R).map(|s| {
+        let mut a = [0; R];
+        a.copy_from_slice(&s);
+        a
+    });
+}
+
+// The r-subsets of 1..n which contain the increasing subset s.
+pub fn containing(n: usize, r: usize, s: &[usize]) -> impl Iterator<Item = Vec<usize>> + '_ {
+    let others: Vec<usize> = (1..=n).filter(|p| !s.contains(p)).collect();
+    return subsets(others.len(), r - s.len()).map(move |o| {
+        let mut pts: Vec<usize> = o.iter().map(|i| others[i-1]).chain(s.iter().cloned()).collect();
+        pts.sort_unstable();
+        pts
+    });
+}
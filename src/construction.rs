@@ -0,0 +1,162 @@
+use std::fs;
+use crate::args::{Args, die};
+use crate::comb::{binom, cd, ntriples, subsets, tuples};
+use crate::quads::{Point, orientation};
+use crate::relations::push_relation;
+use crate::solver::add;
+use crate::tables::{Tables, all_quads};
+
+// The construction of Erdős and Szekeres of 2^(k-2) points without a convex
+// k-gon, showing f(k) > 2^(k-2). Points have distinct increasing x, and a cup
+// (resp. cap) is a sequence of them all whose consecutive triples have
+// orientation 1 (resp. -1).
+
+// The largest |dy/dx| between two of the points, rounded up.
+fn steepest(pts: &[Point]) -> i64 {
+    let mut s = 0;
+    for (i,p) in pts.iter().enumerate() {
+        for q in pts[i+1..].iter() {
+            let (dx, dy) = ((q.0-p.0).abs(), (q.1-p.1).abs());
+            s = s.max((dy + dx - 1) / dx);
+        }
+    }
+    return s;
+}
+
+fn shift(pts: &[Point], dx: i64, dy: i64) -> Vec<Point> {
+    return pts.iter().map(|(x,y)| (x+dx, y+dy)).collect();
+}
+
+fn span(pts: &[Point], c: fn(&Point) -> i64) -> (i64,i64) {
+    return (pts.iter().map(c).min().unwrap(), pts.iter().map(c).max().unwrap());
+}
+
+// C(a+b-4,a-2) points without an a-cup or a b-cap: the ones for (a-1,b), and
+// to their right and high above the ones for (a,b-1), so that every slope
+// between the two halves is steeper than the slopes inside them. A cup can then
+// only take one point of the right half, and a cap one of the left half.
+pub fn cups_caps(a: usize, b: usize) -> Vec<Point> {
+    if a <= 2 || b <= 2 { return vec![(0,0)]; }
+    let (l, r) = (cups_caps(a-1, b), cups_caps(a, b-1));
+    let s = steepest(&l).max(steepest(&r));
+    let ((lx0,lx1), (_,ly1)) = (span(&l, |p| p.0), span(&l, |p| p.1));
+    let ((rx0,rx1), (ry0,_)) = (span(&r, |p| p.0), span(&r, |p| p.1));
+    // the gap between the halves is as wide as both, which keeps the slopes
+    // across them within a small factor of each other.
+    let gap = (lx1-lx0) + (rx1-rx0) + 1;
+    let dx = lx1 + gap - rx0;
+    let dy = ly1 - ry0 + (s+1)*(rx1 + dx - lx0);
+    let mut pts = l;
+    pts.extend(shift(&r, dx, dy));
+    return pts;
+}
+
+// The 2^(k-2) points: blocks T_i = cups_caps(k-i, i+2) for i = 0..=k-2, left to
+// right on a steep cap, far from each other. A cup takes at most one point
+// before its last block, and a cap at most one point in each block after its
+// first, so a convex polygon from block i to block j has at most i+1 points in
+// T_i, one in each block in between and k-j-1 in T_j.
+pub fn points(k: usize) -> Vec<Point> {
+    let blocks: Vec<Vec<Point>> = (0..=k-2).map(|i| cups_caps(k-i, i+2)).collect();
+    let s = blocks.iter().map(|b| steepest(b)).max().unwrap();
+    let w = blocks.iter().map(|b| { let (x0,x1) = span(b, |p| p.0); x1-x0 }).max().unwrap();
+    let h = blocks.iter().map(|b| { let (y0,y1) = span(b, |p| p.1); y1-y0 }).max().unwrap();
+    let step = w + 8*(k as i64)*(w+1);
+    let slope = 2*(s+1) + 8*h/step + 1;
+    let mut pts = Vec::new();
+    let mut y = 0;
+    for (i,b) in blocks.iter().enumerate() {
+        let (x0,_) = span(b, |p| p.0);
+        let (y0,_) = span(b, |p| p.1);
+        pts.extend(shift(b, i as i64*step - x0, y - y0));
+        y -= slope*(i as i64+1)*step;
+    }
+    return pts;
+}
+
+// The orientations of the triples of the points, indexed by cd (points are
+// numbered from 1).
+pub fn signature(pts: &[Point]) -> Vec<i8> {
+    let mut f = vec![0; ntriples(pts.len())];
+    for [a,b,c] in tuples::<3>(pts.len()) { f[cd(a,b,c)] = orientation(pts[a-1], pts[b-1], pts[c-1]); }
+    return f;
+}
+
+// The size of the largest convex polygon of the points, as the largest cup plus
+// the largest cap between two points p and q, less p and q.
+pub fn largest_convex(pts: &[Point]) -> usize {
+    let n = pts.len();
+    let o = |a: usize, b: usize, c: usize| orientation(pts[a], pts[b], pts[c]);
+    let mut best = n.min(2);
+    for p in 0..n {
+        // chains[s][i*n+j]: the longest chain from p with sign s whose last two
+        // points are i and j, 0 if there is none.
+        let mut chains = [vec![0; n*n], vec![0; n*n]];
+        for (si,sign) in [1,-1].into_iter().enumerate() {
+            let c = &mut chains[si];
+            for j in p+1..n {
+                c[p*n+j] = 2;
+                for i in p+1..j {
+                    c[i*n+j] = (p..i).filter(|h| c[h*n+i] > 0 && o(*h,i,j) == sign).map(|h| c[h*n+i] + 1).max().unwrap_or(0);
+                }
+            }
+        }
+        for q in p+1..n {
+            let longest = |c: &Vec<usize>| (p..q).map(|i| c[i*n+q]).max().unwrap();
+            best = best.max(longest(&chains[0]) + longest(&chains[1]) - 2);
+        }
+    }
+    return best;
+}
+
+// Whether an assignment of all the triples of n points satisfies no convex
+// relation of k points and has every quadrilateral valid, as add finds when
+// every triple is given at once. The quadrilaterals go through add, and the
+// relations, too many to keep for k = 7, are generated one k-subset at a time:
+// with every triple set, a relation is satisfied when all its sign*f agree.
+pub fn kgon_free(k: usize, f: &[i8]) -> bool {
+    let n = (3..).find(|n| ntriples(*n) == f.len()).unwrap();
+    let t = Tables::restricted(k, n, vec![], all_quads(n));
+    let mut g = f.to_vec();
+    let mut hs: Vec<usize> = (0..f.len()).collect();
+    let all = hs.clone();
+    if !add(&t, &mut g, &mut hs, &all) { return false; }
+    let mut rel = Vec::new();
+    for pts in subsets(n, k) {
+        for mask in 0..1 << (k-3) {
+            rel.clear();
+            push_relation(&pts, mask, &mut rel);
+            let x = rel[0].0*f[rel[0].1];
+            if rel.iter().all(|(s,r)| s*f[*r] == x) { return false; }
+        }
+    }
+    return true;
+}
+
+// Relations kgon_free goes through in reasonable time, up to k = 7.
+const MAX_RELATIONS: usize = 100_000_000;
+
+// he construct --k 6 [-o points.txt] [--signature sig.txt]: the construction,
+// checked geometrically, and by the relations of k points while there are at
+// most MAX_RELATIONS of them.
+pub fn main(args: &Args) {
+    let k = args.num("k", 6);
+    if !(4..=10).contains(&k) { die("need 4 <= k <= 10"); }
+    let pts = points(k);
+    let f = signature(&pts);
+    if f.contains(&0) { die("points not in general position"); }
+    if let Some(out) = args.get("o") {
+        let text: String = pts.iter().map(|(x,y)| format!("{} {}\n", x, y)).collect();
+        fs::write(out, text).unwrap_or_else(|e| die(&format!("{}: {}", out, e)));
+    }
+    if let Some(out) = args.get("signature") {
+        let text: String = f.iter().map(|x| if *x == 1 { '+' } else { '-' }).chain(['\n']).collect();
+        fs::write(out, text).unwrap_or_else(|e| die(&format!("{}: {}", out, e)));
+    }
+    let n = pts.len();
+    let count = binom(n, k) << (k-3);
+    let relations = if count <= MAX_RELATIONS {
+        if kgon_free(k, &f) { "unsatisfied".to_string() } else { "SATISFIED".to_string() }
+    } else { format!("not checked ({} of them)", count) };
+    println!("k: {}  points: {}  largest convex polygon: {}  relations: {}", k, n, largest_convex(&pts), relations);
+}
@@ -0,0 +1,113 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::{self, Write};
+use rayon::prelude::*;
+use crate::args::{Args, die};
+use crate::estimate::{Rng, estimate};
+use crate::signature;
+use crate::solver::{Limits, Run, prefixes, try_signature};
+use crate::szekeres6;
+use crate::tables::Tables;
+
+// Cube and conquer for hard signatures: the search tree of a signature is split
+// in cubes, the subtrees below some nodes given by their prefix (see
+// solver::Run), which are solved independently and whose counts are merged.
+// A cube file has a line "<id> <v> <prefix..>" per cube; solving cubes gives a
+// line "<id> <checkpoint>" per cube with the counts of its run.
+
+// The cubes of v: the nodes d levels below the root, and then while probes say
+// some cube has more than max nodes, the nodes one level below it.
+fn split(t: &Tables, v: u64, d: usize, max: Option<f64>, probes: usize, rng: &mut Rng) -> Vec<Vec<u64>> {
+    let mut out = Vec::new();
+    let mut todo = prefixes(t, v, &[], d);
+    while let Some(c) = todo.pop() {
+        let big = max.is_some_and(|m| estimate(t, v, &c, probes, rng).nodes > m);
+        let below = if big { prefixes(t, v, &c, 1) } else { vec![] };
+        if below.len() > 1 || below.first().is_some_and(|b| *b != c) { todo.extend(below); }
+        else { out.push(c); }
+    }
+    if out.is_empty() { out.push(vec![]); }
+    out.sort();
+    return out;
+}
+
+fn read_lines(path: &str) -> Vec<Vec<u64>> {
+    let text = fs::read_to_string(path).unwrap_or_else(|e| die(&format!("{}: {}", path, e)));
+    return text.lines().filter(|l| !l.trim().is_empty()).map(|l| {
+        l.split_whitespace().map(|w| w.parse().ok()).collect::<Option<Vec<u64>>>()
+            .unwrap_or_else(|| die(&format!("{}: invalid line {}", path, l)))
+    }).collect();
+}
+
+// he cubes split <signature> [--k 6 --n 17] [--depth 2] [--max-estimate x --probes 100] -o cubes.txt
+fn split_main(args: &Args) {
+    let s = signature::from_arg(args.rest.get(1).unwrap_or_else(|| die("missing signature")), args.num("k", 6));
+    let out = args.required("o");
+    let t = szekeres6::instance(args);
+    let max = args.get("max-estimate").map(|_| args.num("max-estimate", 0.0));
+    let mut rng = Rng::new(args.num("seed", 1));
+    let cubes = split(&t, s.index, args.num("depth", 2), max, args.num("probes", 100), &mut rng);
+    let mut text = String::new();
+    for (id,c) in cubes.iter().enumerate() {
+        text += &format!("{} {}{}\n", id, s.index, c.iter().map(|u| format!(" {}", u)).collect::<String>());
+    }
+    fs::write(out, text).unwrap_or_else(|e| die(&format!("{}: {}", out, e)));
+    println!("{} cubes written to {}", cubes.len(), out);
+}
+
+// he cubes solve cubes.txt [--from i] [--to j]: solves the cubes with ids in
+// [i, j), printing their counts.
+fn solve_main(args: &Args) {
+    let path = args.rest.get(1).unwrap_or_else(|| die("missing cube file"));
+    let t = szekeres6::instance(args);
+    let (from, to) = (args.num("from", 0), args.num("to", u64::MAX));
+    let limits = Limits { max_nodes: None, max_time: None, ..szekeres6::limits(args) };
+    let cubes: Vec<Vec<u64>> = read_lines(path).into_iter().filter(|c| c.len() >= 2 && from <= c[0] && c[0] < to).collect();
+    rayon::ThreadPoolBuilder::new().num_threads(args.num("threads", 1)).build_global().unwrap();
+    cubes.par_iter().for_each(|c| {
+        let mut run = Run::new(c[1], limits);
+        run.prefix = c[2..].to_vec();
+        try_signature(&t, &mut run);
+        let line = format!("{} {}\n", c[0], run.checkpoint());
+        let _ = io::stdout().lock().write_all(line.as_bytes());
+    });
+}
+
+// he cubes merge cubes.txt results.txt..: sums the counts of the cubes of each
+// signature, which must all be solved.
+fn merge_main(args: &Args) {
+    let path = args.rest.get(1).unwrap_or_else(|| die("missing cube file"));
+    let t = szekeres6::instance(args);
+    let mut cubes: BTreeMap<u64, u64> = BTreeMap::new();
+    for c in read_lines(path) {
+        if c.len() < 2 { die(&format!("{}: invalid cube {:?}", path, c)); }
+        cubes.insert(c[0], c[1]);
+    }
+    let mut runs: BTreeMap<u64, Run> = BTreeMap::new();
+    let mut solved: BTreeSet<u64> = BTreeSet::new();
+    for r in args.rest[2..].iter() {
+        let text = fs::read_to_string(r).unwrap_or_else(|e| die(&format!("{}: {}", r, e)));
+        for l in text.lines().filter(|l| !l.trim().is_empty()) {
+            let (id, c) = l.split_once(' ').unwrap_or_else(|| die(&format!("{}: invalid line {}", r, l)));
+            let id: u64 = id.parse().unwrap_or_else(|_| die(&format!("{}: invalid line {}", r, l)));
+            let run = Run::resume(c, Limits::default()).unwrap_or_else(|e| die(&format!("{}: {}", r, e)));
+            if cubes.get(&id) != Some(&run.v) { die(&format!("{}: cube {} is not in {}", r, id, path)); }
+            if !solved.insert(id) { continue; }
+            runs.entry(run.v).or_insert_with(|| Run::new(run.v, Limits::default())).merge(&run);
+        }
+    }
+    let missing: Vec<u64> = cubes.keys().filter(|id| !solved.contains(id)).copied().collect();
+    if !missing.is_empty() {
+        die(&format!("{} cubes without result, the first ones {:?}", missing.len(), &missing[..missing.len().min(10)]));
+    }
+    for run in runs.values() { println!("{}", szekeres6::result(&t, run)); }
+}
+
+pub fn main(args: &Args) {
+    match args.rest.first().map(|s| s.as_str()) {
+        Some("split") => split_main(args),
+        Some("solve") => solve_main(args),
+        Some("merge") => merge_main(args),
+        _ => die("usage: he cubes split|solve|merge .."),
+    }
+}
@@ -0,0 +1,87 @@
+use crate::args::{Args, die};
+use crate::comb::{binom, cd, subsets};
+use crate::extremal::satisfiable;
+use crate::relations::Materialized;
+use crate::tables::Tables;
+
+// The cup-cap numbers of Erdős and Szekeres: f(a,b) is the least n such that
+// every n points have an a-cup (a chain of a points all whose consecutive
+// triples are 1) or a b-cap (all -1). It is C(a+b-4,a-2)+1. They are found by
+// the same search, with the cups and caps forbidden as chains (see
+// solver::add), possibly together with the convex relations of a k-gon.
+
+// The chains of len points among 1..n with every triple equal to sign, as the
+// relations of Materialized, each one as len-2 pairs (sign, triple).
+pub struct Chains {
+    pub len: usize,
+    rels: Materialized,
+}
+
+impl Chains {
+    pub fn new(n: usize, len: usize, sign: i8) -> Chains {
+        let rels = subsets(n, len).map(|pts| pts.windows(3).map(|w| (sign, cd(w[0],w[1],w[2]))).collect()).collect();
+        return Chains { len, rels: Materialized::new(n, rels) };
+    }
+
+    pub fn cups(n: usize, len: usize) -> Chains {
+        return Chains::new(n, len, 1);
+    }
+
+    pub fn caps(n: usize, len: usize) -> Chains {
+        return Chains::new(n, len, -1);
+    }
+
+    // The chains involving the triple t, packed (see relations::pack_pair).
+    pub fn involving(&self, t: usize) -> std::slice::ChunksExact<'_, u32> {
+        return self.rels.at(t).chunks_exact(self.len-2);
+    }
+}
+
+// C(a+b-4,a-2)+1.
+pub fn formula(a: usize, b: usize) -> usize {
+    return binom(a+b-4, a-2) + 1;
+}
+
+// The tables for n points without an a-cup or a b-cap, and without a convex
+// k-gon if k is given. With no k, windows have up to 6 points.
+pub fn tables(a: usize, b: usize, k: Option<usize>, n: usize) -> Tables {
+    let t = match k {
+        Some(k) => Tables::new(k, n),
+        None => Tables::windows_only(n.min(6), n),
+    };
+    return t.with_chains(Chains::cups(n, a)).with_chains(Chains::caps(n, b));
+}
+
+// The signatures to try for u_1: every one on a chain of n-k+1 windows. Unlike
+// szekeres6::signatures the first triple is not fixed to 1, since negating
+// swaps cups and caps.
+pub fn signatures(t: &Tables) -> Vec<u64> {
+    let omega = &t.omega;
+    return omega.sigs.iter().enumerate().filter(|(i,_)| omega.reach[*i] == t.nwindows()).map(|(_,v)| *v).collect();
+}
+
+// The least n forcing an a-cup, a b-cap (or a convex k-gon), calling report
+// with every n tried and whether it has an assignment without them.
+pub fn number(a: usize, b: usize, k: Option<usize>, mut report: impl FnMut(usize, bool)) -> usize {
+    let mut n = a.max(b).max(k.unwrap_or(3));
+    loop {
+        let t = tables(a, b, k, n);
+        let sat = satisfiable(&t, &signatures(&t));
+        report(n, sat);
+        if !sat { return n; }
+        n += 1;
+    }
+}
+
+// he cupcap --cup 4 --cap 5 [--k 6]
+pub fn main(args: &Args) {
+    let (a, b) = (args.num("cup", 4), args.num("cap", 4));
+    if a < 3 || b < 3 { die("--cup and --cap must be at least 3"); }
+    let k = args.get("k").map(|_| args.num("k", 6));
+    if k.is_some_and(|k| !(4..=6).contains(&k)) { die("--k must be between 4 and 6"); }
+    let f = number(a, b, k, |n, sat| println!("n: {}  {}", n, if sat { "satisfiable" } else { "none" }));
+    match k {
+        None => println!("f({},{}) = {}  C({},{})+1 = {}", a, b, f, a+b-4, a-2, formula(a, b)),
+        Some(k) => println!("{}-cup, {}-cap or convex {}-gon forced from {} points", a, b, k, f),
+    }
+}
@@ -0,0 +1,108 @@
+use std::time::Instant;
+use crate::args::{Args, die};
+use crate::signature;
+use crate::solver::{candidates, open_window, restore, set_u};
+use crate::tables::{self, Tables};
+
+// Estimation of the size of the search tree of a signature, by Knuth's random
+// probes: a descent from the root picks uniformly one of the candidates of each
+// node that pass set_u, and the product of the number of choices along the way
+// is an unbiased estimate of the number of nodes at each depth. It counts nodes,
+// not time: the bit checks at the leaves, which may cost far more than the
+// nodes above them, are not run, so only the number of leaves reaching them is
+// estimated.
+
+// splitmix64, enough to pick children.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        return Rng(seed);
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        return z ^ (z >> 31);
+    }
+
+    pub fn below(&mut self, n: usize) -> usize {
+        return (self.next_u64() % n as u64) as usize;
+    }
+}
+
+// Estimated number of nodes of search (calls to it) and of leaves (nodes where
+// every window is assigned, which go through the bit checks).
+#[derive(Clone, Copy, Default)]
+pub struct Estimate {
+    pub nodes: f64,
+    pub leaves: f64,
+}
+
+fn descend(t: &Tables, v: u64, prefix: &[u64], rng: &mut Rng) -> Estimate {
+    let mut e = Estimate::default();
+    let mut f = vec![0; t.ntriples()];
+    let mut hs = Vec::new();
+    if !set_u(t, &mut f, &mut hs, 1, v) { return e; }
+    for u in prefix.iter() {
+        match open_window(t, &f) {
+            Some(j) if set_u(t, &mut f, &mut hs, j, *u) => {}
+            _ => return e,
+        }
+    }
+    let mut w = 1.0;
+    loop {
+        e.nodes += w;
+        let j = match open_window(t, &f) {
+            None => { e.leaves += w; return e; }
+            Some(j) => j,
+        };
+        let mut children = Vec::new();
+        for u in candidates(t, &f, j).collect::<Vec<u64>>() {
+            let k = hs.len();
+            if set_u(t, &mut f, &mut hs, j, u) { children.push(u); }
+            restore(&mut f, &mut hs, k);
+        }
+        if children.is_empty() { return e; }
+        w *= children.len() as f64;
+        let u = children[rng.below(children.len())];
+        set_u(t, &mut f, &mut hs, j, u);
+    }
+}
+
+// Mean of probes random descents for the signature v in u_1, below the node
+// given by prefix (see solver::Run).
+pub fn estimate(t: &Tables, v: u64, prefix: &[u64], probes: usize, rng: &mut Rng) -> Estimate {
+    let mut e = Estimate::default();
+    for _ in 0..probes {
+        let d = descend(t, v, prefix, rng);
+        e.nodes += d.nodes / probes as f64;
+        e.leaves += d.leaves / probes as f64;
+    }
+    return e;
+}
+
+// Estimates every signature of sigs and lists them from the largest tree (by
+// estimated nodes) to the smallest.
+pub fn rank(t: &Tables, sigs: &[u64], probes: usize, seed: u64) {
+    let mut rng = Rng::new(seed);
+    let mut est: Vec<(u64, Estimate)> = sigs.iter().map(|v| (*v, estimate(t, *v, &[], probes, &mut rng))).collect();
+    est.sort_by(|a,b| b.1.nodes.total_cmp(&a.1.nodes));
+    let total: f64 = est.iter().map(|(_,e)| e.nodes).sum();
+    for (i,(v,e)) in est.iter().enumerate() {
+        println!("{:>4} idx: {:>8}  nodes: {:>10.3e}  leaves: {:>10.3e}  share: {:.4}", i+1, v, e.nodes, e.leaves, e.nodes / total);
+    }
+}
+
+// he estimate <signature> [--n 17] [--probes 1000] [--seed 1]
+pub fn main(args: &Args) {
+    let s = signature::from_arg(args.rest.first().unwrap_or_else(|| die("missing signature")), args.num("k", 6));
+    let t = tables::from_args(args, s.k, args.num("n", 17));
+    let probes = args.num("probes", 1000);
+    let now = Instant::now();
+    let e = estimate(&t, s.index, &[], probes, &mut Rng::new(args.num("seed", 1)));
+    println!("idx: {}  probes: {}  nodes: {:.3e}  leaves: {:.3e}  probes took: {:.2}s",
+             s.index, probes, e.nodes, e.leaves, now.elapsed().as_secs_f32());
+}
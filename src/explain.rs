@@ -0,0 +1,211 @@
+use std::collections::BTreeMap;
+use crate::args::{Args, die};
+use crate::comb::uncd;
+use crate::relations::Rel;
+use crate::signature;
+use crate::solver::{add, bit_check, candidates, open_window, restore, set_u};
+use crate::tables::{self, Quad, Tables};
+
+// Explanations of the refutation of a signature. Every conflict met by the
+// search (a window that cannot be set, or a leaf refuted by an r-bit-check) is
+// reduced to a core: a set of triple assignments, convex relations and
+// quadrilaterals that already leads add to a contradiction, minimal in the
+// sense that dropping any one of them does not.
+
+// A conflict found by the search: after assigning the triples of assumed, every
+// way of assigning the triples of probe is refuted by add.
+pub struct Conflict {
+    pub what: String,
+    pub assumed: Vec<(usize,i8)>,
+    pub probe: Vec<usize>,
+}
+
+pub struct Core {
+    pub assumed: Vec<(usize,i8)>,
+    pub probe: Vec<usize>,
+    pub rels: Vec<Rel>,
+    pub quads: Vec<Quad>,
+}
+
+// Whether assumed and every assignment of probe are refuted by add with the
+// constraints of t. seen marks every triple that got assigned on the way.
+fn refuted(t: &Tables, assumed: &[(usize,i8)], probe: &[usize], seen: &mut [bool]) -> bool {
+    let mut f = vec![0; t.ntriples()];
+    let mut hs = Vec::new();
+    for (x,v) in assumed.iter() {
+        if f[*x] == 0 { f[*x] = *v; hs.push(*x); }
+        else if f[*x] != *v { return true; }
+    }
+    let added = hs.clone();
+    let b = add(t, &mut f, &mut hs, &added);
+    for x in hs.iter() { seen[*x] = true; }
+    if !b { return true; }
+    let k = hs.len();
+    let r = probe.len();
+    'signs: for signs in 0..1 << r {
+        let mut added = Vec::new();
+        for (b,x) in probe.iter().enumerate() {
+            let v = if (signs >> (r-1-b)) & 1 == 0 { 1 } else { -1 };
+            if f[*x] == 0 { f[*x] = v; hs.push(*x); added.push(*x); }
+            else if f[*x] != v { restore(&mut f, &mut hs, k); continue 'signs; }
+        }
+        let b = add(t, &mut f, &mut hs, &added);
+        for x in hs.iter() { seen[*x] = true; }
+        restore(&mut f, &mut hs, k);
+        if b { return false; }
+    }
+    return true;
+}
+
+// Deletion based minimization: drops chunks of items while the rest is still
+// refuted, halving the size of the chunks down to single items. The result is
+// refuted, and is not any more once any of its items is dropped.
+fn minimize<T: Clone>(items: Vec<T>, mut refuted: impl FnMut(&[T]) -> bool) -> Vec<T> {
+    let mut core = items;
+    let mut size = core.len().div_ceil(2).max(1);
+    loop {
+        let mut i = 0;
+        while i < core.len() {
+            let end = (i + size).min(core.len());
+            let rest: Vec<T> = core[..i].iter().chain(core[end..].iter()).cloned().collect();
+            if refuted(&rest) { core = rest; } else { i = end; }
+        }
+        if size == 1 { return core; }
+        size = size.div_ceil(2);
+    }
+}
+
+// A minimal core of the conflict c: first the assignments are minimized using
+// every constraint, then the constraints among the triples that assignments
+// and probes reach.
+pub fn core(t: &Tables, c: &Conflict) -> Core {
+    let mut seen = vec![false; t.ntriples()];
+    let assumed = minimize(c.assumed.clone(), |a| refuted(t, a, &c.probe, &mut seen));
+    seen.fill(false);
+    refuted(t, &assumed, &c.probe, &mut seen);
+    let mut rels: Vec<Rel> = Vec::new();
+    let mut quads: Vec<Quad> = Vec::new();
+    let mut buf = Vec::new();
+    for x in (0..t.ntriples()).filter(|x| seen[*x]) {
+        for rel in t.relations(x, &mut buf) {
+            if rel.iter().all(|(_,r)| seen[*r]) && rel.iter().map(|(_,r)| *r).min() == Some(x) {
+                rels.push(rel.to_vec());
+            }
+        }
+        for (a,b,c,d) in t.quads[x].iter() {
+            if [*a,*b,*c,*d].into_iter().find(|i| seen[*i]) == Some(x) { quads.push((*a,*b,*c,*d)); }
+        }
+    }
+    // relations and quadrilaterals are minimized together, tagged by kind.
+    let cons: Vec<(Option<Rel>, Quad)> = rels.into_iter().map(|r| (Some(r), (0,0,0,0)))
+        .chain(quads.into_iter().map(|q| (None, q))).collect();
+    let split = |cons: &[(Option<Rel>, Quad)]| {
+        let rels = cons.iter().filter_map(|(r,_)| r.clone()).collect::<Vec<Rel>>();
+        let quads = cons.iter().filter(|(r,_)| r.is_none()).map(|(_,q)| *q).collect::<Vec<Quad>>();
+        (rels, quads)
+    };
+    let cons = minimize(cons, |cons| {
+        let (rels, quads) = split(cons);
+        refuted(&Tables::restricted(t.k, t.n, rels, quads).propagating_like(t), &assumed, &c.probe, &mut seen)
+    });
+    let (rels, quads) = split(&cons);
+    return Core { assumed, probe: c.probe.clone(), rels, quads };
+}
+
+// Whether the relations and quadrilaterals of the core, with the other
+// conditions of t, refute its assignments and probes.
+pub fn refutes(t: &Tables, c: &Core) -> bool {
+    let r = Tables::restricted(t.k, t.n, c.rels.clone(), c.quads.clone()).propagating_like(t);
+    return refuted(&r, &c.assumed, &c.probe, &mut vec![false; t.ntriples()]);
+}
+
+// The triples of the windows u_1..u_j with their values in f, followed by the
+// ones of u_{j+1} with the values of the packed v.
+fn decisions(t: &Tables, f: &[i8], j: usize, v: Option<u64>) -> Vec<(usize,i8)> {
+    let mut d = BTreeMap::new();
+    for i in 1..=j {
+        for x in t.window(i).iter() { d.insert(*x, f[*x]); }
+    }
+    if let Some(v) = v {
+        for (i,x) in t.window(j+1).iter().enumerate() { d.insert(*x, t.omega.get(v,i)); }
+    }
+    return d.into_iter().collect();
+}
+
+// The search of solver::search, collecting the conflicts of the given stage (0
+// for windows, r for the r-bit-check) until there are limit of them.
+fn collect(t: &Tables, f: &mut Vec<i8>, hs: &mut Vec<usize>, stage: Option<usize>, limit: usize, out: &mut Vec<Conflict>) {
+    if out.len() >= limit { return; }
+    match open_window(t,f) {
+        None => for r in 1..=3 {
+            if let Some(probe) = bit_check(t,f,hs,r) {
+                if stage.is_none() || stage == Some(r) {
+                    let what = format!("leaf refuted by the {}-bit-check", r);
+                    out.push(Conflict { what, assumed: decisions(t,f,t.nwindows(),None), probe });
+                }
+                return;
+            }
+        },
+        Some(j) => for v in candidates(t,f,j).collect::<Vec<u64>>() {
+            let k = hs.len();
+            let b = set_u(t,f,hs,j,v);
+            if b { collect(t,f,hs,stage,limit,out); }
+            restore(f,hs,k);
+            if !b && (stage.is_none() || stage == Some(0)) {
+                out.push(Conflict { what: format!("u_{} = {}", j, v), assumed: decisions(t,f,j-1,Some(v)), probe: vec![] });
+            }
+            if out.len() >= limit { return; }
+        }
+    }
+}
+
+fn triple(x: usize) -> String {
+    let (a,b,c) = uncd(x);
+    return format!("({},{},{})", a, b, c);
+}
+
+fn print_core(c: &Core) {
+    println!("  core: {} assignments, {} probes, {} relations, {} quadrilaterals",
+             c.assumed.len(), c.probe.len(), c.rels.len(), c.quads.len());
+    let signs: Vec<String> = c.assumed.iter().map(|(x,v)| format!("{}{}", triple(*x), if *v == 1 { '+' } else { '-' })).collect();
+    println!("    assume {}", signs.join(" "));
+    if !c.probe.is_empty() {
+        println!("    probe  {}", c.probe.iter().map(|x| triple(*x)).collect::<Vec<String>>().join(" "));
+    }
+    for rel in c.rels.iter() {
+        let r: Vec<String> = rel.iter().map(|(s,x)| format!("{}{}", if *s == 1 { '+' } else { '-' }, triple(*x))).collect();
+        println!("    relation {}", r.join(" "));
+    }
+    for (x,_,_,w) in c.quads.iter() {
+        let ((a,b,c),(_,_,d)) = (uncd(*x), uncd(*w));
+        println!("    quadrilateral ({},{},{},{})", a, b, c, d);
+    }
+}
+
+// The first limit conflicts of the given stage (any if None) met by the search
+// of the signature v.
+pub fn conflicts(t: &Tables, v: u64, stage: Option<usize>, limit: usize) -> Vec<Conflict> {
+    let mut f = vec![0; t.ntriples()];
+    let mut hs = Vec::new();
+    let mut out = Vec::new();
+    if set_u(t, &mut f, &mut hs, 1, v) {
+        collect(t, &mut f, &mut hs, stage, limit, &mut out);
+    } else if stage.is_none() || stage == Some(0) {
+        out.push(Conflict { what: format!("u_1 = {}", v), assumed: decisions(t, &f, 0, Some(v)), probe: vec![] });
+    }
+    return out;
+}
+
+// he explain <signature> [--n 17] [--stage 0..3] [--limit 3]
+pub fn main(args: &Args) {
+    let s = signature::from_arg(args.rest.first().unwrap_or_else(|| die("missing signature")), args.num("k", 6));
+    let n = args.num("n", 17);
+    let stage = args.get("stage").map(|_| args.num("stage", 0));
+    let t = tables::from_args(args, s.k, n);
+    let conflicts = conflicts(&t, s.index, stage, args.num("limit", 3));
+    if conflicts.is_empty() { println!("no conflicts found"); }
+    for (i,c) in conflicts.iter().enumerate() {
+        println!("conflict {}: {}", i+1, c.what);
+        print_core(&core(&t, c));
+    }
+}
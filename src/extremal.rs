@@ -0,0 +1,116 @@
+use std::collections::BTreeSet;
+use std::fs;
+use rayon::prelude::*;
+use crate::args::{Args, die};
+use crate::comb::{cd, tuples};
+use crate::solver::{Limits, Run, add, restore, try_signature, unpack};
+use crate::szekeres6::signatures;
+use crate::tables::{self, Tables};
+
+// Every assignment of all the triples of n points with no convex k-gon, up to
+// the symmetries of signature::Signature::orbit. The leaves of the search that
+// pass the one-bit-check are completed by trying both signs for each free
+// triple, so that at n = 2^(k-2) the extremal configurations are listed, and
+// not only counted.
+
+// Appends to out every completion of f.
+pub fn complete(t: &Tables, f: &mut Vec<i8>, hs: &mut Vec<usize>, out: &mut Vec<Vec<i8>>) {
+    let x = match f.iter().position(|y| *y == 0) {
+        None => { out.push(f.clone()); return; }
+        Some(x) => x,
+    };
+    for y in [1,-1] {
+        let k = hs.len();
+        f[x] = y;
+        hs.push(x);
+        if add(t, f, hs, &vec![x]) { complete(t, f, hs, out); }
+        restore(f, hs, k);
+    }
+}
+
+// Whether f has some completion, leaving f as it was.
+pub fn extends(t: &Tables, f: &mut Vec<i8>, hs: &mut Vec<usize>) -> bool {
+    let x = match f.iter().position(|y| *y == 0) {
+        None => return true,
+        Some(x) => x,
+    };
+    for y in [1,-1] {
+        let k = hs.len();
+        f[x] = y;
+        hs.push(x);
+        let found = add(t, f, hs, &vec![x]) && extends(t, f, hs);
+        restore(f, hs, k);
+        if found { return true; }
+    }
+    return false;
+}
+
+// The assignment of the points reflected by x -> -x (see Signature::mirror).
+pub fn mirror(n: usize, f: &[i8]) -> Vec<i8> {
+    let mut g = vec![0; f.len()];
+    for [a,b,c] in tuples::<3>(n) { g[cd(n+1-c,n+1-b,n+1-a)] = f[cd(a,b,c)]; }
+    return g;
+}
+
+pub fn signs(f: &[i8]) -> String {
+    return f.iter().map(|x| if *x == 1 { '+' } else { '-' }).collect();
+}
+
+// The smallest of the signs of f, of its mirror and of their negations, '+'
+// being smaller than '-'.
+pub fn canonical(n: usize, f: &[i8]) -> String {
+    let m = mirror(n, f);
+    let neg = |g: &[i8]| g.iter().map(|x| -x).collect::<Vec<i8>>();
+    return [signs(f), signs(&m), signs(&neg(f)), signs(&neg(&m))].into_iter().min().unwrap();
+}
+
+// The number of complete assignments, and their canonical forms. The search
+// fixes the triple 123 to 1, and negating gives the others.
+pub fn enumerate(t: &Tables) -> (u64, BTreeSet<String>) {
+    let found: Vec<Vec<Vec<i8>>> = signatures(t).par_iter().map(|v| {
+        let mut run = Run::new(*v, Limits::default());
+        run.stages = 1;
+        run.stage1 = Some(Vec::new());
+        try_signature(t, &mut run);
+        let mut out = Vec::new();
+        for p in run.stage1.unwrap().iter() {
+            let mut f = unpack(p, t.ntriples());
+            let mut hs: Vec<usize> = (0..f.len()).filter(|i| f[*i] != 0).collect();
+            complete(t, &mut f, &mut hs, &mut out);
+        }
+        out
+    }).collect();
+    let all: Vec<&Vec<i8>> = found.iter().flatten().collect();
+    return (2 * all.len() as u64, all.into_iter().map(|f| canonical(t.n, f)).collect());
+}
+
+// Whether some assignment of every triple satisfies the tables: a leaf of the
+// search of one of the signatures sigs passing the one-bit-check which extends
+// to one.
+pub fn satisfiable(t: &Tables, sigs: &[u64]) -> bool {
+    return sigs.iter().any(|v| {
+        let mut run = Run::new(*v, Limits::default());
+        run.stages = 1;
+        run.stage1 = Some(Vec::new());
+        try_signature(t, &mut run);
+        run.stage1.unwrap().iter().any(|p| {
+            let mut f = unpack(p, t.ntriples());
+            let mut hs: Vec<usize> = (0..f.len()).filter(|i| f[*i] != 0).collect();
+            extends(t, &mut f, &mut hs)
+        })
+    });
+}
+
+// he extremal [--k 5] [--n 2^(k-2)] -o extremal5.txt [--threads 1]: writes the
+// canonical form of every assignment without a convex k-gon, one per line.
+pub fn main(args: &Args) {
+    let k = args.num("k", 5);
+    let n = args.num("n", 1 << (k-2));
+    if k < 4 || n < k { die("need 4 <= k <= n"); }
+    let out = args.required("o");
+    rayon::ThreadPoolBuilder::new().num_threads(args.num("threads", 1)).build_global().unwrap();
+    let (total, classes) = enumerate(&tables::from_args(args, k, n));
+    let text: String = classes.iter().map(|c| format!("{}\n", c)).collect();
+    fs::write(out, text).unwrap_or_else(|e| die(&format!("{}: {}", out, e)));
+    println!("k: {}  n: {}  assignments: {}  up to symmetry: {}", k, n, total, classes.len());
+}
@@ -0,0 +1,164 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use crate::args::{Args, die};
+use crate::comb::{cd, ntriples, subsets, tuples};
+use crate::extremal::satisfiable;
+use crate::quads;
+use crate::relations::{Rel, chains, convex_relations};
+use crate::szekeres6::signatures;
+use crate::tables::Tables;
+
+// Empty convex k-gons (k-holes): h(k) is the least n such that n points always
+// have a convex k-gon with no other point inside. A hole is a convex relation
+// together with, for every point q strictly between its first and last point
+// and not in it, the triples (a,q,b) of the edges ab of both chains passing
+// over or under q. q is outside the polygon when one of them has the
+// orientation of its chain, and the hole is there when the relation is
+// satisfied and every such q is outside. A window of k consecutive points has
+// no point between its own, so Omega is the usual one.
+
+pub struct Hole {
+    pub rel: Rel,
+    // for each point q, the pairs (sign, triple): q is outside when sign*f[triple]
+    // is the value of the relation for one of them.
+    pub outside: Vec<[(i8,usize);2]>,
+}
+
+// The holes of k points among 1..n, and at[t] the ones involving the triple t.
+pub struct Holes {
+    pub all: Vec<Hole>,
+    pub at: Vec<Vec<u32>>,
+}
+
+// The triple of q and the edge of the chain passing over or under it.
+fn edge(chain: &[usize], q: usize) -> usize {
+    let i = chain.iter().position(|p| *p > q).unwrap();
+    return cd(chain[i-1], q, chain[i]);
+}
+
+// The holes on the points pts, one per convex relation.
+fn holes_of(pts: &[usize]) -> Vec<Hole> {
+    let k = pts.len();
+    return convex_relations(pts).into_iter().enumerate().map(|(mask,rel)| {
+        let (lower, upper) = chains(pts, mask);
+        let outside = (pts[0]+1..pts[k-1]).filter(|q| !pts.contains(q))
+            .map(|q| [(1, edge(&lower, q)), (-1, edge(&upper, q))]).collect();
+        Hole { rel, outside }
+    }).collect();
+}
+
+impl Holes {
+    pub fn new(k: usize, n: usize) -> Holes {
+        let all: Vec<Hole> = subsets(n, k).flat_map(|pts| holes_of(&pts)).collect();
+        let mut at: Vec<Vec<u32>> = vec![vec![]; ntriples(n)];
+        for (i,h) in all.iter().enumerate() {
+            let mut ts: Vec<usize> = h.rel.iter().chain(h.outside.iter().flatten()).map(|(_,t)| *t).collect();
+            ts.sort();
+            ts.dedup();
+            for t in ts { at[t].push(i as u32); }
+        }
+        return Holes { all, at };
+    }
+}
+
+// What a hole needs for the relation to have value x under f: None if it cannot
+// be there, or else the pairs of its relation not yet set, and the points q not
+// yet known to be outside, each with the pairs that would put it outside and
+// are not yet set.
+pub fn pending(h: &Hole, f: &[i8], x: i8) -> Option<(Rel, Vec<Rel>)> {
+    let mut rel = Vec::new();
+    for (s,r) in h.rel.iter() {
+        if f[*r] == 0 { rel.push((*s,*r)); }
+        else if s*f[*r] != x { return None; }
+    }
+    let mut qs = Vec::new();
+    for o in h.outside.iter() {
+        if o.iter().any(|(s,r)| s*f[*r] == x) { continue; }
+        let free: Rel = o.iter().filter(|(_,r)| f[*r] == 0).copied().collect();
+        if free.is_empty() { return None; }
+        qs.push(free);
+    }
+    return Some((rel, qs));
+}
+
+// The least n whose points always have a k-hole, calling report with every n
+// tried and whether it has an assignment without one.
+pub fn number(k: usize, mut report: impl FnMut(usize, bool)) -> usize {
+    let mut n = k;
+    loop {
+        let t = Tables::holes(k, n);
+        let sat = satisfiable(&t, &signatures(&t));
+        report(n, sat);
+        if !sat { return n; }
+        n += 1;
+    }
+}
+
+// Gives every clause of the instance to emit, numbering the auxiliary
+// variables in inside.
+fn clauses(k: usize, n: usize, inside: &mut BTreeMap<[i64;2], i64>, emit: &mut impl FnMut(&[i64]) -> io::Result<()>) -> io::Result<()> {
+    let nt = ntriples(n);
+    let lit = |s: i8, t: usize, x: i8| if s*x == 1 { (t+1) as i64 } else { -((t+1) as i64) };
+    emit(&[1])?;
+    let real = quads::realizable();
+    for [a,b,c,d] in tuples::<4>(n) {
+        let ts = [cd(a,b,c), cd(a,b,d), cd(a,c,d), cd(b,c,d)];
+        for m in 0..16 {
+            let v: Vec<i8> = (0..4).map(|i| if (m >> (3-i)) & 1 == 1 { 1 } else { -1 }).collect();
+            if real.contains(&(v[0],v[1],v[2],v[3])) { continue; }
+            emit(&(0..4).map(|i| lit(-v[i], ts[i], 1)).collect::<Vec<i64>>())?;
+        }
+    }
+    for pts in subsets(n, k) {
+        for h in holes_of(&pts) {
+            for x in [1,-1] {
+                let mut c: Vec<i64> = h.rel.iter().map(|(s,r)| lit(-s, *r, x)).collect();
+                for o in h.outside.iter() {
+                    let key = [lit(-o[0].0, o[0].1, x), lit(-o[1].0, o[1].1, x)];
+                    let next = (nt + inside.len() + 1) as i64;
+                    c.push(*inside.entry(key).or_insert(next));
+                }
+                emit(&c)?;
+            }
+        }
+    }
+    for (key,z) in inside.iter() {
+        for l in key { emit(&[-z, *l])?; }
+    }
+    return Ok(());
+}
+
+// Writes the instance "n points without a k-hole" in DIMACS CNF. Variable t+1
+// is the triple with cd = t, true for orientation 1, and f(1,2,3) = 1. Every
+// quadruple of points has one of the realizable signatures, and for every hole
+// and value x, either the relation is not satisfied or some q is inside, that
+// is some auxiliary variable implying that both pairs of q are not x holds.
+// The clauses are generated twice, first to count them for the header.
+pub fn write_cnf(k: usize, n: usize, w: &mut impl Write) -> io::Result<()> {
+    let (mut inside, mut count) = (BTreeMap::new(), 0);
+    clauses(k, n, &mut inside, &mut |_| { count += 1; Ok(()) })?;
+    writeln!(w, "c {}-holes on {} points, see src/holes.rs", k, n)?;
+    writeln!(w, "p cnf {} {}", ntriples(n) + inside.len(), count)?;
+    clauses(k, n, &mut inside, &mut |c| {
+        for l in c { write!(w, "{} ", l)?; }
+        writeln!(w, "0")
+    })?;
+    return w.flush();
+}
+
+// he holes --k 5: h(k) by the search; he holes --k 6 --n 30 --cnf hex30.cnf: the
+// instance in CNF.
+pub fn main(args: &Args) {
+    let k = args.num("k", 5);
+    if !(4..=6).contains(&k) { die("--k must be between 4 and 6"); }
+    if let Some(out) = args.get("cnf") {
+        let n = args.num("n", 30);
+        if n < k { die("need n >= k"); }
+        let mut w = BufWriter::new(fs::File::create(out).unwrap_or_else(|e| die(&format!("{}: {}", out, e))));
+        write_cnf(k, n, &mut w).unwrap_or_else(|e| die(&format!("{}: {}", out, e)));
+        return;
+    }
+    let h = number(k, |n, sat| println!("n: {}  {}", n, if sat { "satisfiable" } else { "none" }));
+    println!("h({}) = {}", k, h);
+}
@@ -0,0 +1,25 @@
+#![allow(clippy::needless_return, clippy::ptr_arg)]
+
+pub mod args;
+pub mod binfile;
+pub mod brute_search;
+pub mod comb;
+pub mod construction;
+pub mod cubes;
+pub mod cupcap;
+pub mod estimate;
+pub mod explain;
+pub mod extremal;
+pub mod holes;
+pub mod net;
+pub mod omega;
+pub mod queue;
+pub mod quads;
+pub mod quints;
+pub mod relations;
+pub mod signature;
+pub mod solver;
+pub mod survivors;
+pub mod szekeres5;
+pub mod szekeres6;
+pub mod tables;
@@ -1,9 +1,28 @@
-//mod szekeres5;
-mod szekeres6;
 //use std::env;
 
+use he::args::{Args, die};
+use he::{construction, cubes, cupcap, estimate, explain, extremal, holes, net, queue, signature, survivors, szekeres6, tables};
+
 fn main() {
     //env::set_var("RUST_BACKTRACE", "full");
     //szekeres5::main();
-    szekeres6::main();
+    let args = Args::parse(std::env::args().skip(1));
+    match args.cmd.as_deref() {
+        None | Some("run") => szekeres6::main(&args),
+        Some("precompute") => tables::main(&args),
+        Some("sig") => signature::main(&args),
+        Some("explain") => explain::main(&args),
+        Some("estimate") => estimate::main(&args),
+        Some("enqueue") => queue::enqueue(&args),
+        Some("worker") if args.get("connect").is_some() => net::worker(&args),
+        Some("worker") => queue::worker(&args),
+        Some("coordinator") => net::coordinator(&args),
+        Some("cubes") => cubes::main(&args),
+        Some("stages") => survivors::main(&args),
+        Some("extremal") => extremal::main(&args),
+        Some("construct") => construction::main(&args),
+        Some("cupcap") => cupcap::main(&args),
+        Some("holes") => holes::main(&args),
+        Some(c) => die(&format!("unknown command {}", c)),
+    }
 }
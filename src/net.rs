@@ -0,0 +1,171 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+use crate::args::{Args, die};
+use crate::solver::{Limits, Run, prefixes, try_signature};
+use crate::szekeres6;
+use crate::tables::Tables;
+
+// A coordinator handing out work units to workers over TCP. A unit is a
+// signature for u_1 and a prefix of signatures for u_2, u_3, .. (see
+// solver::prefixes), so that the units of a signature split its tree. The
+// protocol is made of lines, each request of a worker getting one answer:
+//   GET                  ->  UNIT <id> <v> <u_2> ..  |  WAIT  |  DONE
+//   RESULT <id> <run>    ->  OK
+// where <run> is the checkpoint of the finished run of the unit, and anything
+// else is answered by ERR. The units of a worker which disconnects before
+// sending their result are issued again. Once every unit is done the
+// coordinator waits for the workers connected to ask and get DONE, so that a
+// worker which loses its connection knows that it failed.
+
+struct Unit {
+    v: u64,
+    prefix: Vec<u64>,
+}
+
+struct State {
+    units: Vec<Unit>,
+    todo: VecDeque<usize>,
+    finished: Vec<bool>,
+    // for each signature, its units left and the sum of the finished ones.
+    sigs: BTreeMap<u64, (usize, Run)>,
+    left: usize,
+    workers: usize,
+}
+
+type Shared = Arc<(Mutex<State>, Condvar)>;
+
+fn serve(t: &Tables, stream: TcpStream, shared: &Shared) -> io::Result<()> {
+    let (lock, cvar) = &**shared;
+    stream.set_nodelay(true)?;
+    let mut w = stream.try_clone()?;
+    lock.lock().unwrap().workers += 1;
+    let mut mine: BTreeSet<usize> = BTreeSet::new();
+    let mut answer = |line: &str| -> io::Result<String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let mut st = lock.lock().unwrap();
+        match words.as_slice() {
+            ["GET"] => {
+                if let Some(id) = st.todo.pop_front() {
+                    mine.insert(id);
+                    let u = &st.units[id];
+                    let prefix: String = u.prefix.iter().map(|p| format!(" {}", p)).collect();
+                    return Ok(format!("UNIT {} {}{}", id, u.v, prefix));
+                }
+                return Ok(if st.left == 0 { "DONE" } else { "WAIT" }.to_string());
+            }
+            ["RESULT", id, run @ ..] => {
+                let (Ok(id), Ok(run)) = (id.parse::<usize>(), Run::resume(&run.join(" "), Limits::default())) else {
+                    return Ok("ERR".to_string());
+                };
+                if id >= st.units.len() || run.v != st.units[id].v { return Ok("ERR".to_string()); }
+                mine.remove(&id);
+                if !st.finished[id] {
+                    st.finished[id] = true;
+                    st.left -= 1;
+                    let v = st.units[id].v;
+                    let (left, total) = st.sigs.get_mut(&v).unwrap();
+                    total.merge(&run);
+                    *left -= 1;
+                    if *left == 0 { println!("{}", szekeres6::result(t, total)); }
+                    if st.left == 0 { cvar.notify_all(); }
+                }
+                return Ok("OK".to_string());
+            }
+            _ => return Ok("ERR".to_string()),
+        }
+    };
+    let mut result = Ok(());
+    for line in BufReader::new(stream).lines() {
+        let a = line.and_then(|l| answer(&l));
+        match a.and_then(|a| w.write_all(format!("{}\n", a).as_bytes())) {
+            Ok(()) => {}
+            Err(e) => { result = Err(e); break; }
+        }
+    }
+    let mut st = lock.lock().unwrap();
+    for id in mine {
+        if !st.finished[id] { st.todo.push_front(id); }
+    }
+    st.workers -= 1;
+    cvar.notify_all();
+    return result;
+}
+
+// he coordinator --listen 127.0.0.1:7117 [--k 6 --n 17] [--depth 1]: splits
+// every signature in the units of depth --depth, serves them, prints the result
+// of each signature once all its units are done, and at the end the total of
+// assignments surviving every check.
+pub fn coordinator(args: &Args) {
+    let addr = args.required("listen");
+    let depth = args.num("depth", 0);
+    let t = Arc::new(szekeres6::instance(args));
+    let mut st = State { units: Vec::new(), todo: VecDeque::new(), finished: Vec::new(), sigs: BTreeMap::new(), left: 0, workers: 0 };
+    for v in szekeres6::signatures(&t) {
+        let mut ps = prefixes(&t, v, &[], depth);
+        if ps.is_empty() { ps.push(vec![]); }
+        st.sigs.insert(v, (ps.len(), Run::new(v, Limits::default())));
+        for prefix in ps { st.units.push(Unit { v, prefix }); }
+    }
+    st.left = st.units.len();
+    st.todo = (0..st.left).collect();
+    st.finished = vec![false; st.left];
+    eprintln!("{} units for {} signatures", st.left, st.sigs.len());
+    let listener = TcpListener::bind(addr).unwrap_or_else(|e| die(&format!("{}: {}", addr, e)));
+    eprintln!("listening on {}", listener.local_addr().map(|a| a.to_string()).unwrap_or_else(|_| addr.to_string()));
+    let shared: Shared = Arc::new((Mutex::new(st), Condvar::new()));
+    {
+        let (t, shared) = (t.clone(), shared.clone());
+        thread::spawn(move || for stream in listener.incoming().flatten() {
+            let (t, shared) = (t.clone(), shared.clone());
+            thread::spawn(move || {
+                let peer = stream.peer_addr().map(|a| a.to_string()).unwrap_or_default();
+                if let Err(e) = serve(&t, stream, &shared) { eprintln!("he: {}: {}", peer, e); }
+            });
+        });
+    }
+    let (lock, cvar) = &*shared;
+    let st = cvar.wait_while(lock.lock().unwrap(), |st| st.left > 0).unwrap();
+    println!("total: {}", st.sigs.values().map(|(_,run)| run.survivors()).sum::<u64>());
+    drop(cvar.wait_while(st, |st| st.workers > 0).unwrap());
+}
+
+// he worker --connect 127.0.0.1:7117 [--k 6 --n 17]: runs units until the
+// coordinator has no more of them, and fails if the connection is lost before.
+// --k and --n must be the ones of the coordinator.
+pub fn worker(args: &Args) {
+    let addr = args.required("connect");
+    let t = szekeres6::instance(args);
+    let limits = Limits { max_nodes: None, max_time: None, ..szekeres6::limits(args) };
+    let stream = TcpStream::connect(addr).unwrap_or_else(|e| die(&format!("{}: {}", addr, e)));
+    stream.set_nodelay(true).unwrap_or_else(|e| die(&format!("{}: {}", addr, e)));
+    let mut w = stream.try_clone().unwrap_or_else(|e| die(&format!("{}: {}", addr, e)));
+    let mut r = BufReader::new(stream);
+    let mut ask = |req: &str| -> io::Result<String> {
+        w.write_all(format!("{}\n", req).as_bytes())?;
+        let mut line = String::new();
+        if r.read_line(&mut line)? == 0 { return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "connection closed")); }
+        return Ok(line.trim().to_string());
+    };
+    let fail = |e: io::Error| -> ! { die(&format!("{}: {}", addr, e)) };
+    loop {
+        let a = ask("GET").unwrap_or_else(|e| fail(e));
+        let words: Vec<&str> = a.split_whitespace().collect();
+        match words.as_slice() {
+            ["UNIT", id, v, prefix @ ..] => {
+                let parsed = v.parse().ok().zip(prefix.iter().map(|p| p.parse().ok()).collect::<Option<Vec<u64>>>());
+                let Some((v, prefix)) = parsed else { die(&format!("bad unit {}", a)) };
+                let mut run = Run::new(v, limits);
+                run.prefix = prefix;
+                try_signature(&t, &mut run);
+                ask(&format!("RESULT {} {}", id, run.checkpoint())).unwrap_or_else(|e| fail(e));
+            }
+            ["WAIT"] => thread::sleep(Duration::from_secs(1)),
+            ["DONE"] => return,
+            _ => die(&format!("unexpected answer {}", a)),
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use crate::binfile::{invalid, read_u32, read_u64, write_u32, write_u64};
+use crate::comb::{cd, ntriples, tuples};
+use crate::quads::VALID;
+use crate::relations::{Rel, convex_relations};
+use crate::signature::{MAX_K, Signature};
+
+// Valid signatures of windows of k points (Omega), generalizing concave6. A
+// signature assigns an orientation to each of the C(k,3) triples of points 1..k
+// and is packed in a u64 as its index: bit b-1-i is set iff the triple with
+// cd = i is assigned 1, b being the number of triples.
+
+const MAGIC: &[u8;8] = b"HEOMEGA\0";
+const VERSION: u32 = 1;
+
+// Omega interned: ids are given in increasing order of index, so sigs is sorted
+// and the id of a packed signature is found by binary search. comp[id] holds
+// the ids of the signatures compatible with sigs[id], restricted to those lying
+// on some chain of l windows. reach[id] is the length of the longest chain
+// starting at sigs[id] (up to l) and chains[id] the number of chains of l
+// windows starting at it.
+pub struct Omega {
+    pub k: usize,
+    pub b: usize,
+    pub sigs: Vec<u64>,
+    pub comp: Vec<Vec<u32>>,
+    pub reach: Vec<usize>,
+    pub chains: Vec<u64>,
+}
+
+impl Omega {
+    // Omega for windows of k points, for chains of l windows.
+    pub fn new(k: usize, l: usize) -> Omega {
+        return Omega::with_quads(k, l, true);
+    }
+
+    // Omega with or without the quadrilateral conditions: without them, every
+    // signature satisfying none of the convex relations is a window.
+    pub fn with_quads(k: usize, l: usize, quads: bool) -> Omega {
+        return Omega::constrained(k, l, quads, convex_relations(&(1..=k).collect::<Vec<usize>>()));
+    }
+
+    // Omega for windows of k points satisfying none of the relations rels
+    // (among the triples of 1..k), instead of the convex ones.
+    pub fn constrained(k: usize, l: usize, quads: bool, rels: Vec<Rel>) -> Omega {
+        let sigs = enumerate(k, quads, rels);
+        let comp = compatible(k, &sigs);
+        return Omega::from_table(k, sigs, comp, l);
+    }
+
+    // Like with_quads, but reading the table from path if it holds one for k,
+    // and writing it there otherwise.
+    pub fn cached(k: usize, l: usize, quads: bool, path: &Path) -> io::Result<Omega> {
+        if k > MAX_K { return Err(invalid(&format!("signatures of {} points do not fit in a u64", k))); }
+        if path.exists() {
+            if let Ok(omega) = Omega::load(path, l) {
+                if omega.k == k { return Ok(omega); }
+            }
+        }
+        let omega = Omega::with_quads(k, l, quads);
+        omega.save(path)?;
+        return Ok(omega);
+    }
+
+    pub fn from_table(k: usize, sigs: Vec<u64>, mut comp: Vec<Vec<u32>>, l: usize) -> Omega {
+        let reach = longest_chains(&comp, l);
+        let back = longest_chains(&reversed(&comp), l);
+        for (id,c) in comp.iter_mut().enumerate() {
+            if reach[id] + back[id] <= l { c.clear(); }
+            c.retain(|i| reach[*i as usize] + back[*i as usize] > l);
+        }
+        let chains = count_chains(&comp, l);
+        return Omega { k, b: ntriples(k), sigs, comp, reach, chains };
+    }
+
+    pub fn id(&self, v: u64) -> Option<usize> {
+        return self.sigs.binary_search(&v).ok();
+    }
+
+    // value of the triple with cd = i in the packed signature v.
+    pub fn get(&self, v: u64, i: usize) -> i8 {
+        return match (v >> (self.b - 1 - i)) & 1 {1 => 1, _ => -1};
+    }
+
+    pub fn signature(&self, v: u64) -> Signature {
+        return Signature::from_index(self.k, v);
+    }
+
+    // Writes the table as: magic, version, and the table itself (see write_table).
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut w = BufWriter::new(File::create(path)?);
+        w.write_all(MAGIC)?;
+        write_u32(&mut w, VERSION)?;
+        self.write_table(&mut w)?;
+        return w.flush();
+    }
+
+    pub fn load(path: &Path, l: usize) -> io::Result<Omega> {
+        let mut r = BufReader::new(File::open(path)?);
+        let mut magic = [0; 8];
+        r.read_exact(&mut magic)?;
+        if &magic != MAGIC { return Err(invalid("not an omega table")); }
+        if read_u32(&mut r)? != VERSION { return Err(invalid("unsupported omega table version")); }
+        let (k,sigs,comp) = Omega::read_table(&mut r)?;
+        return Ok(Omega::from_table(k, sigs, comp, l));
+    }
+
+    // Writes k, the number of signatures, the signatures, and for each of them
+    // the length of its unrestricted compatibility list followed by the list.
+    pub fn write_table(&self, w: &mut impl Write) -> io::Result<()> {
+        write_u32(w, self.k as u32)?;
+        write_u32(w, self.sigs.len() as u32)?;
+        for v in self.sigs.iter() { write_u64(w, *v)?; }
+        for c in compatible(self.k, &self.sigs).iter() {
+            write_u32(w, c.len() as u32)?;
+            for i in c.iter() { write_u32(w, *i)?; }
+        }
+        return Ok(());
+    }
+
+    pub fn read_table(r: &mut impl Read) -> io::Result<(usize, Vec<u64>, Vec<Vec<u32>>)> {
+        let k = read_u32(r)? as usize;
+        let len = read_u32(r)? as usize;
+        let sigs = (0..len).map(|_| read_u64(r)).collect::<io::Result<Vec<u64>>>()?;
+        let mut comp = Vec::with_capacity(len);
+        for _ in 0..len {
+            let m = read_u32(r)? as usize;
+            let c = (0..m).map(|_| read_u32(r)).collect::<io::Result<Vec<u32>>>()?;
+            if c.iter().any(|i| *i as usize >= len) { return Err(invalid("compatible id out of range")); }
+            comp.push(c);
+        }
+        return Ok((k,sigs,comp));
+    }
+}
+
+// All signatures of k points which satisfy none of the relations rels and, if
+// quads, whose quadrilaterals are VALID, in increasing order. Triples are
+// assigned in the order of cd, that is point by point, and every constraint is
+// checked as soon as its last triple is assigned. k is at most MAX_K.
+pub fn enumerate(k: usize, quads: bool, all_rels: Vec<Rel>) -> Vec<u64> {
+    assert!(k <= MAX_K, "signatures of {} points do not fit in a u64", k);
+    let b = ntriples(k);
+    let mut last: Vec<Vec<[usize;4]>> = vec![vec![]; b];
+    for [p,q,r,s] in tuples::<4>(k).filter(|_| quads) {
+        last[cd(q,r,s)].push([cd(p,q,r),cd(p,q,s),cd(p,r,s),cd(q,r,s)]);
+    }
+    let quads = last;
+    let mut rels: Vec<Vec<Rel>> = vec![vec![]; b];
+    for rel in all_rels {
+        let last = rel.iter().map(|(_,t)| *t).max().unwrap();
+        rels[last].push(rel);
+    }
+    let mut out = Vec::new();
+    extend(&quads, &rels, &mut vec![0; b], 0, 0, &mut out);
+    return out;
+}
+
+fn extend(quads: &[Vec<[usize;4]>], rels: &[Vec<Rel>], v: &mut Vec<i8>, t: usize, packed: u64, out: &mut Vec<u64>) {
+    if t == v.len() { out.push(packed); return; }
+    'x: for (x,bit) in [(-1,0),(1,1)] {
+        v[t] = x;
+        for [p,q,r,s] in quads[t].iter() {
+            if !VALID.contains(&(v[*p],v[*q],v[*r],v[*s])) { continue 'x; }
+        }
+        for rel in rels[t].iter() {
+            let (s0,t0) = rel[0];
+            if rel.iter().all(|(s,t)| s*v[*t] == s0*v[t0]) { continue 'x; }
+        }
+        extend(quads, rels, v, t+1, (packed << 1) | bit, out);
+    }
+    v[t] = 0;
+}
+
+// For every v in Omega its compatible set of signatures: the ones that can be
+// assigned to u_{j+1} if v is assigned to u_{j}. See (3.4) and the first note on
+// efficiency at the end of page 8. The triples of points 1..k-1 are the first
+// positions, so u follows v iff the triples of points 2..k of v, read in that
+// order, equal the high bits of u.
+pub fn compatible(k: usize, omega: &[u64]) -> Vec<Vec<u32>> {
+    let (b,h) = (ntriples(k), ntriples(k-1));
+    let mut heads: BTreeMap<u64, Vec<u32>> = BTreeMap::new();
+    for (id,u) in omega.iter().enumerate() {
+        heads.entry(u >> (b-h)).or_default().push(id as u32);
+    }
+    let tail = |v: u64| tuples::<3>(k-1)
+        .fold(0, |t,[p,q,r]| t | ((v >> (b-1-cd(p+1,q+1,r+1))) & 1) << (h-1-cd(p,q,r)));
+    return omega.iter().map(|v| heads.get(&tail(*v)).cloned().unwrap_or_default()).collect();
+}
+
+// Length of the longest chain starting at each vertex of the window graph given
+// by comp, capped at l.
+fn longest_chains(comp: &[Vec<u32>], l: usize) -> Vec<usize> {
+    let mut len = vec![1; comp.len()];
+    for _ in 1..l {
+        len = comp.iter().map(|c| 1 + c.iter().map(|i| len[*i as usize]).max().unwrap_or(0)).collect();
+    }
+    return len;
+}
+
+fn reversed(comp: &[Vec<u32>]) -> Vec<Vec<u32>> {
+    let mut rcomp = vec![vec![]; comp.len()];
+    for (id,c) in comp.iter().enumerate() {
+        for i in c.iter() { rcomp[*i as usize].push(id as u32); }
+    }
+    return rcomp;
+}
+
+// Number of chains of l windows starting at each vertex, by iterating the
+// transfer matrix of the window graph.
+fn count_chains(comp: &[Vec<u32>], l: usize) -> Vec<u64> {
+    let mut count = vec![1_u64; comp.len()];
+    for _ in 1..l {
+        count = comp.iter().map(|c| c.iter().fold(0_u64, |s,i| s.saturating_add(count[*i as usize]))).collect();
+    }
+    return count;
+}
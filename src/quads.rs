@@ -0,0 +1,93 @@
+// Geometric conditions on four points a < b < c < d. A quadrilateral signature
+// is the tuple of orientations of (abc,abd,acd,bcd).
+
+pub type QuadV = (i8,i8,i8,i8);
+
+// valid signatures for a quadrilateral (see (2.3)). The ones that include 0's
+// are the ones from which a valid signature in (2.3) can be reached.
+pub const VALID: [QuadV;65] = [(1,1,1,1),(0,1,1,1),(1,0,1,1),(1,1,0,1),(1,1,1,0),(0,0,1,1),(0,1,0,1),(0,1,1,0),(1,0,0,1),(1,0,1,0),(1,1,0,0),(1,0,0,0),(0,1,0,0),(0,0,1,0),(0,0,0,1),(0,0,0,0),(1,1,1,-1),(0,1,1,-1),(1,0,1,-1),(1,1,0,-1),(0,0,1,-1),(0,1,0,-1),(1,0,0,-1),(0,0,0,-1),(1,1,-1,-1),(0,1,-1,-1),(1,0,-1,-1),(1,1,-1,0),(0,0,-1,-1),(0,1,-1,0),(1,0,-1,0),(0,0,-1,0),(1,-1,-1,-1),(0,-1,-1,-1),(1,-1,0,-1),(1,-1,-1,0),(0,-1,0,-1),(0,-1,-1,0),(1,-1,0,0),(0,-1,0,0),(-1,-1,-1,-1),(-1,0,-1,-1),(-1,-1,0,-1),(-1,-1,-1,0),(-1,0,0,-1),(-1,0,-1,0),(-1,-1,0,0),(-1,0,0,0),(-1,-1,-1,1),(0,-1,-1,1),(-1,0,-1,1),(-1,-1,0,1),(0,0,-1,1),(0,-1,0,1),(-1,0,0,1),(-1,-1,1,1),(0,-1,1,1),(-1,0,1,1),(-1,-1,1,0),(0,-1,1,0),(-1,0,1,0),(-1,1,1,1),(-1,1,0,1),(-1,1,1,0),(-1,1,0,0)];
+
+// forced quadrilateral signatures. For example the ony valid signature
+// that extends (-1,0,0,-1) is (-1,-1,-1,-1).
+pub fn fillquad (xs: QuadV) -> Option<QuadV> {
+    match xs {
+        (-1,0,0,-1)  => Some((-1,-1,-1,-1)),
+        (0,0,-1,1)   => Some((-1,-1,-1,1)),
+        (0,0,1,-1)   => Some((1,1,1,-1)),
+        (0,-1,1,0)   => Some((-1,-1,1,1)),
+        (0,1,-1,0)   => Some((1,1,-1,-1)),
+        (-1,1,0,0)   => Some((-1,1,1,1)),
+        (1,-1,0,0)   => Some((1,-1,-1,-1)),
+        (1,0,0,1)    => Some((1,1,1,1)),
+        (-1,0,-1,-1) => Some((-1,-1,-1,-1)),
+        (-1,-1,0,-1) => Some((-1,-1,-1,-1)),
+        (0,-1,-1,1)  => Some((-1,-1,-1,1)),
+        (-1,0,-1,1)  => Some((-1,-1,-1,1)),
+        (-1,-1,1,0)  => Some((-1,-1,1,1)),
+        (0,-1,1,1)   => Some((-1,-1,1,1)),
+        (0,1,-1,-1)  => Some((1,1,-1,-1)),
+        (-1,1,0,1)   => Some((-1,1,1,1)),
+        (0,1,1,-1)   => Some((1,1,1,-1)),
+        (-1,1,1,0)   => Some((-1,1,1,1)),
+        (1,-1,0,-1)  => Some((1,-1,-1,-1)),
+        (1,-1,-1,0)  => Some((1,-1,-1,-1)),
+        (1,0,1,-1)   => Some((1,1,1,-1)),
+        (1,0,1,1)    => Some((1,1,1,1)),
+        (1,1,-1,0)   => Some((1,1,-1,-1)),
+        (1,1,0,1)    => Some((1,1,1,1)),
+        _            => None
+    }
+}
+
+// The tables above come from the orientations that four points with increasing
+// x can have, which are computed here from points on a small grid so that they
+// can be checked against VALID and fillquad.
+
+pub type Point = (i64,i64);
+
+// The orientation of the triangle pqr: 1 counterclockwise, -1 clockwise, 0 if
+// the points are aligned. Computed in i128, so that any coordinates will do.
+pub fn orientation(p: Point, q: Point, r: Point) -> i8 {
+    let (p, q, r) = ((p.0 as i128, p.1 as i128), (q.0 as i128, q.1 as i128), (r.0 as i128, r.1 as i128));
+    return ((q.0-p.0)*(r.1-p.1) - (q.1-p.1)*(r.0-p.0)).signum() as i8;
+}
+
+// Signatures of the quadrilaterals a < b < c < d in general position with
+// coordinates in 0..6, sorted.
+pub fn realizable() -> Vec<QuadV> {
+    let mut out: Vec<QuadV> = Vec::new();
+    let grid: Vec<Point> = (0..6).flat_map(|x| (0..6).map(move |y| (x,y))).collect();
+    for a in grid.iter() { for b in grid.iter() { for c in grid.iter() { for d in grid.iter() {
+        if !(a.0 < b.0 && b.0 < c.0 && c.0 < d.0) { continue; }
+        let q = (orientation(*a,*b,*c), orientation(*a,*b,*d), orientation(*a,*c,*d), orientation(*b,*c,*d));
+        if q.0 == 0 || q.1 == 0 || q.2 == 0 || q.3 == 0 { continue; }
+        if !out.contains(&q) { out.push(q); }
+    }}}}
+    out.sort();
+    return out;
+}
+
+fn extends(xs: QuadV, q: QuadV) -> bool {
+    let (a,b) = ([xs.0,xs.1,xs.2,xs.3], [q.0,q.1,q.2,q.3]);
+    return a.iter().zip(b.iter()).all(|(x,y)| *x == 0 || x == y);
+}
+
+// The partial signatures (0 for unassigned) with some realizable extension, as
+// VALID but sorted.
+pub fn valid() -> Vec<QuadV> {
+    let real = realizable();
+    let signs = [-1,0,1];
+    let mut out = Vec::new();
+    for a in signs { for b in signs { for c in signs { for d in signs {
+        if real.iter().any(|q| extends((a,b,c,d), *q)) { out.push((a,b,c,d)); }
+    }}}}
+    return out;
+}
+
+// The single realizable extension of a partial signature, when it has one and
+// some triple is unassigned, as fillquad.
+pub fn forced(xs: QuadV) -> Option<QuadV> {
+    if xs.0 != 0 && xs.1 != 0 && xs.2 != 0 && xs.3 != 0 { return None; }
+    let ext: Vec<QuadV> = realizable().into_iter().filter(|q| extends(xs, *q)).collect();
+    return if ext.len() == 1 { Some(ext[0]) } else { None };
+}
@@ -0,0 +1,188 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{Duration, SystemTime};
+use crate::args::{Args, die};
+use crate::solver::{Limits, Run, try_signature};
+use crate::szekeres6;
+use crate::tables::Tables;
+
+// A job queue in a directory, for workers in several processes, possibly on
+// several machines sharing the directory. A job is a file holding the
+// checkpoint of a run (see solver::Run), named by its signature, and lives in
+// one of:
+//   todo/<idx>                   waiting to be claimed
+//   running/<idx>.<host>.<pid>   claimed by the worker with that pid on host
+//   done/<idx>                   the result line of the finished run
+// Workers claim jobs by renaming them from todo to running, which only one of
+// them can do, and rewrite the checkpoint every slice of the run, so that its
+// modification time tells that the worker is still there. The jobs of a worker
+// that died are put back in todo by the next worker to look at them: a worker
+// on the same host sees that its process is gone, and any worker sees that it
+// has not written its checkpoint for longer than stale (after a reboot, or with
+// the pid reused).
+
+fn dirs(root: &Path) -> [PathBuf;3] {
+    return ["todo", "running", "done"].map(|d| root.join(d));
+}
+
+// Writes the file at once: through a temporary file in the same directory and
+// a rename, so that a killed process never leaves half of it.
+fn write_atomic(path: &Path, contents: &str) -> io::Result<()> {
+    let tmp = path.with_extension(format!("tmp{}", process::id()));
+    fs::write(&tmp, contents)?;
+    return fs::rename(&tmp, path);
+}
+
+pub fn hostname() -> String {
+    return fs::read_to_string("/proc/sys/kernel/hostname").map(|h| h.trim().to_string())
+        .unwrap_or_else(|_| "localhost".to_string());
+}
+
+fn alive(pid: u32) -> bool {
+    return Path::new(&format!("/proc/{}", pid)).exists();
+}
+
+// Whether the worker holding the claim at path is gone (see above).
+fn abandoned(path: &Path, host: &str, pid: u32, stale: Duration) -> bool {
+    if host == hostname() && !alive(pid) { return true; }
+    let age = fs::metadata(path).and_then(|m| m.modified()).ok().and_then(|t| t.elapsed().ok());
+    return age.is_some_and(|a| a > stale);
+}
+
+// Puts back in todo the jobs whose worker is no longer running.
+fn reclaim(root: &Path, stale: Duration) -> io::Result<()> {
+    let [todo, running, _] = dirs(root);
+    for e in fs::read_dir(&running)? {
+        let name = e?.file_name().to_string_lossy().to_string();
+        let Some((idx, claim)) = name.split_once('.') else { continue };
+        let Some((host, pid)) = claim.rsplit_once('.') else { continue };
+        let Ok(pid) = pid.parse::<u32>() else { continue };
+        if !abandoned(&running.join(&name), host, pid, stale) { continue; }
+        // another worker may reclaim it first.
+        let _ = fs::rename(running.join(&name), todo.join(idx));
+    }
+    return Ok(());
+}
+
+// Claims some job in todo, returning its path in running. The claim is dated
+// now, as the job may have waited in todo for longer than stale.
+fn claim(root: &Path) -> io::Result<Option<PathBuf>> {
+    let [todo, running, _] = dirs(root);
+    let mut names: Vec<String> = fs::read_dir(&todo)?
+        .map(|e| e.map(|e| e.file_name().to_string_lossy().to_string()))
+        .collect::<io::Result<Vec<String>>>()?;
+    names.retain(|n| n.parse::<u64>().is_ok());
+    names.sort();
+    for name in names {
+        let path = running.join(format!("{}.{}.{}", name, hostname(), process::id()));
+        let claimed = fs::rename(todo.join(&name), &path)
+            .and_then(|_| fs::File::options().write(true).open(&path)?.set_modified(SystemTime::now()));
+        match claimed {
+            Ok(()) => return Ok(Some(path)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    return Ok(None);
+}
+
+// Whether the claim at path is still there, that is not reclaimed by another
+// worker which took this one for dead.
+fn held(path: &Path) -> io::Result<bool> {
+    return match fs::metadata(path) {
+        Ok(_) => Ok(true),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e),
+    };
+}
+
+// Rewrites the claim at path with the checkpoint c, unless it was reclaimed.
+// The checkpoint is written aside and only renamed over the claim after
+// checking that it is still held, which leaves a short window where a claim
+// reclaimed meanwhile comes back: then both workers run the job, to the same
+// result. Returns whether the claim is still held.
+fn heartbeat(path: &Path, c: &str) -> io::Result<bool> {
+    let tmp = path.with_extension(format!("tmp{}", process::id()));
+    fs::write(&tmp, c)?;
+    if !held(path)? {
+        fs::remove_file(&tmp)?;
+        return Ok(false);
+    }
+    fs::rename(&tmp, path)?;
+    return Ok(true);
+}
+
+// Runs the claimed job at path to the end, in slices of the given length,
+// unless it is reclaimed meanwhile by another worker, which then has the job
+// and the result is dropped.
+fn work(t: &Tables, root: &Path, path: &Path, progress: Option<Duration>, slice: Duration) -> io::Result<()> {
+    let c = match fs::read_to_string(path) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        c => c?,
+    };
+    let limits = Limits { progress, max_nodes: None, max_time: Some(slice) };
+    let mut run = Run::resume(c.trim(), limits).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    loop {
+        try_signature(t, &mut run);
+        if !run.stopped { break; }
+        let c = run.checkpoint();
+        if !heartbeat(path, &c)? { return Ok(()); }
+        run = Run::resume(&c, limits).unwrap();
+    }
+    if !held(path)? { return Ok(()); }
+    let [_, _, done] = dirs(root);
+    write_atomic(&done.join(run.v.to_string()), &format!("{}\n", szekeres6::result(t, &run)))?;
+    // if it was reclaimed since, the other worker writes the same result.
+    return match fs::remove_file(path) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        r => r,
+    };
+}
+
+// he enqueue --dir jobs [--k 6 --n 17]: a job for every signature without one.
+pub fn enqueue(args: &Args) {
+    let root = Path::new(args.required("dir"));
+    let t = szekeres6::instance(args);
+    let fail = |e: io::Error| -> ! { die(&format!("{}: {}", root.display(), e)) };
+    let [todo, running, done] = dirs(root);
+    for d in [&todo, &running, &done] { fs::create_dir_all(d).unwrap_or_else(|e| fail(e)); }
+    let queued = |v: u64| -> io::Result<bool> {
+        if todo.join(v.to_string()).exists() || done.join(v.to_string()).exists() { return Ok(true); }
+        for e in fs::read_dir(&running)? {
+            if e?.file_name().to_string_lossy().split('.').next() == Some(&v.to_string()) { return Ok(true); }
+        }
+        return Ok(false);
+    };
+    let mut added = 0;
+    for v in szekeres6::signatures(&t) {
+        if queued(v).unwrap_or_else(|e| fail(e)) { continue; }
+        write_atomic(&todo.join(v.to_string()), &Run::new(v, Default::default()).checkpoint()).unwrap_or_else(|e| fail(e));
+        added += 1;
+    }
+    println!("{} jobs added to {}", added, todo.display());
+}
+
+// he worker --dir jobs [--k 6 --n 17] [--slice 600] [--stale 3*slice]: runs
+// jobs until there are none left to claim, writing their checkpoint every slice
+// seconds, and taking back the claims not written for stale seconds. Jobs are
+// run to the end, so there is no --max-nodes or --max-seconds.
+pub fn worker(args: &Args) {
+    let root = Path::new(args.required("dir"));
+    if args.get("max-nodes").is_some() || args.get("max-seconds").is_some() {
+        die("workers run their jobs to the end, in slices of --slice seconds");
+    }
+    let slice = args.num("slice", 600.0);
+    let stale = Duration::from_secs_f64(args.num("stale", 3.0*slice));
+    let fail = |e: io::Error| -> ! { die(&format!("{}: {}", root.display(), e)) };
+    let t = szekeres6::instance(args);
+    let progress = szekeres6::limits(args).progress;
+    loop {
+        reclaim(root, stale).unwrap_or_else(|e| fail(e));
+        match claim(root).unwrap_or_else(|e| fail(e)) {
+            None => return,
+            Some(path) => work(&t, root, &path, progress, Duration::from_secs_f64(slice)).unwrap_or_else(|e| fail(e)),
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use crate::comb::{cd, ntriples, subsets, tuples};
+use crate::quads::{self, Point, orientation};
+
+// Geometric conditions on five points p1 < .. < p5, an optional layer on top of
+// the quadrilaterals. A pentagon signature is the tuple of orientations of its
+// 10 triples in the order of cd, packed with bit 9 - cd(a,b,c) set when the
+// orientation of abc is 1. Every assignment of a subset of those triples is
+// given an index in base 3, the triple in position 0 being the most
+// significant digit, which is f + 1.
+
+pub const NTRIPLES: usize = 10;
+pub const NPARTIAL: usize = 59049;
+
+// What the realizable pentagons say about a partial assignment of the triples.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Fill {
+    // no realizable pentagon extends it.
+    Invalid,
+    // several do.
+    Open,
+    // a single one does.
+    Forced(u16),
+}
+
+// The signatures of five points in general position with increasing x and
+// coordinates in 0..8, sorted.
+pub fn realizable() -> Vec<u16> {
+    let mut seen = vec![false; 1 << NTRIPLES];
+    for xs in subsets(8, 5) {
+        for ys in 0..8_usize.pow(5) {
+            let pts: Vec<Point> = (0..5).map(|i| (xs[i] as i64, (ys / 8_usize.pow(i as u32) % 8) as i64)).collect();
+            let mut sig = 0u16;
+            let mut general = true;
+            for [a,b,c] in tuples::<3>(5) {
+                let o = orientation(pts[a-1], pts[b-1], pts[c-1]);
+                general &= o != 0;
+                if o == 1 { sig |= 1 << (NTRIPLES-1-cd(a,b,c)); }
+            }
+            if general { seen[sig as usize] = true; }
+        }
+    }
+    return (0..1 << NTRIPLES).filter(|s| seen[*s as usize]).collect();
+}
+
+// The pentagon signatures all whose quadrilaterals are realizable, sorted. They
+// are the ones the quadrilateral conditions alone allow.
+pub fn quad_consistent() -> Vec<u16> {
+    let real = quads::realizable();
+    let o = |s: u16, a: usize, b: usize, c: usize| if (s >> (NTRIPLES-1-cd(a,b,c))) & 1 == 1 { 1 } else { -1 };
+    return (0..1 << NTRIPLES).filter(|s| tuples::<4>(5).all(|[a,b,c,d]| {
+        real.contains(&(o(*s,a,b,c), o(*s,a,b,d), o(*s,a,c,d), o(*s,b,c,d)))
+    })).collect();
+}
+
+// The digits of a partial index, f + 1 for each position.
+fn digits(mut x: usize) -> [i8; NTRIPLES] {
+    let mut d = [0; NTRIPLES];
+    for i in (0..NTRIPLES).rev() {
+        d[i] = (x % 3) as i8 - 1;
+        x /= 3;
+    }
+    return d;
+}
+
+// For each partial index, what the pentagons in sigs say about it.
+pub fn fill_table(sigs: &[u16]) -> Vec<Fill> {
+    return (0..NPARTIAL).map(|x| {
+        let d = digits(x);
+        let mut ext = sigs.iter().filter(|s| (0..NTRIPLES).all(|i| {
+            d[i] == 0 || (d[i] == 1) == ((**s >> (NTRIPLES-1-i)) & 1 == 1)
+        }));
+        match (ext.next(), ext.next()) {
+            (None, _) => Fill::Invalid,
+            (Some(s), None) => Fill::Forced(*s),
+            _ => Fill::Open,
+        }
+    }).collect();
+}
+
+// The pentagon conditions for n points: at[t] lists the 5-subsets of points
+// containing the triple t, as their 10 triples in the order of a signature.
+pub struct Quints {
+    pub at: Vec<Vec<[usize; NTRIPLES]>>,
+    pub fill: Vec<Fill>,
+}
+
+impl Quints {
+    pub fn new(n: usize) -> Quints {
+        let mut at: Vec<Vec<[usize; NTRIPLES]>> = vec![vec![]; ntriples(n)];
+        for p in tuples::<5>(n) {
+            let mut q = [0; NTRIPLES];
+            for [a,b,c] in tuples::<3>(5) { q[cd(a,b,c)] = cd(p[a-1],p[b-1],p[c-1]); }
+            for t in q { at[t].push(q); }
+        }
+        return Quints { at, fill: fill_table(&realizable()) };
+    }
+}
+
+// The partial index of the triples q under f.
+pub fn index(f: &[i8], q: &[usize; NTRIPLES]) -> usize {
+    return q.iter().fold(0, |x,t| 3*x + (f[*t] + 1) as usize);
+}
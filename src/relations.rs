@@ -0,0 +1,158 @@
+use crate::comb::{cd, containing, ntriples, subsets, uncd};
+
+// A convex relation as (sign, triple) pairs. It is satisfied when sign*f[triple]
+// is the same non zero value for every pair.
+pub type Rel = Vec<(i8,usize)>;
+
+// Convex relations for a k-gon on the points pts (increasing), generalizing the
+// ones for an hexagon (see (4.1)). The inner points are split between a chain
+// through pts[1] and the opposite one, and the k-gon is convex when the first is
+// a cup and the second a cap or the other way around.
+pub fn convex_relations(pts: &[usize]) -> Vec<Rel> {
+    return (0..1_usize << (pts.len()-3)).map(|mask| relation(pts, mask)).collect();
+}
+
+// The relation given by mask (see chains).
+pub fn relation(pts: &[usize], mask: usize) -> Rel {
+    let mut rel = Vec::with_capacity(pts.len()-2);
+    push_relation(pts, mask, &mut rel);
+    return rel;
+}
+
+// Appends the relation given by mask to buf, without building the chains.
+pub fn push_relation(pts: &[usize], mask: usize, buf: &mut Vec<(i8,usize)>) {
+    let k = pts.len();
+    let lower = |i: usize| i == 1 || (mask >> (i-2)) & 1 == 1;
+    for sign in [1,-1] {
+        let (mut a, mut b) = (pts[0], 0);
+        for (i,p) in pts.iter().enumerate().skip(1) {
+            if i < k-1 && lower(i) != (sign == 1) { continue; }
+            if b != 0 { buf.push((sign, cd(a,b,*p))); a = b; }
+            b = *p;
+        }
+    }
+}
+
+// The two chains of the relation given by mask: the one through pts[1], with
+// the inner points whose bit is set, and the opposite one.
+pub fn chains(pts: &[usize], mask: usize) -> (Vec<usize>, Vec<usize>) {
+    let k = pts.len();
+    let (mut lower, mut upper) = (vec![pts[0],pts[1]], vec![pts[0]]);
+    for (i,p) in pts[2..k-1].iter().enumerate() {
+        if (mask >> i) & 1 == 1 { lower.push(*p); } else { upper.push(*p); }
+    }
+    lower.push(pts[k-1]);
+    upper.push(pts[k-1]);
+    return (lower, upper);
+}
+
+// Every convex relation of k points among 1..n.
+pub fn all_relations(k: usize, n: usize) -> Vec<Rel> {
+    return subsets(n, k).flat_map(|pts| convex_relations(&pts)).collect();
+}
+
+// Provides the convex relations of k points among 1..n involving a triple, one
+// after the other in chunks of k-2 pairs. buf is scratch space for providers
+// that build them on demand.
+pub trait Relations: Send + Sync {
+    fn involving<'a>(&'a self, t: usize, buf: &'a mut Vec<(i8,usize)>) -> &'a [(i8,usize)];
+
+    // The same relations packed (see pack_pair), if they are stored that way.
+    fn packed(&self, _: usize) -> Option<&[u32]> {
+        return None;
+    }
+}
+
+// A pair (sign, triple) in 32 bits, the sign in the top one. The propagation
+// goes through every relation of each triple it assigns, and for C(17,6) they
+// only fit in the cache at a quarter of the size of the pairs.
+pub fn pack_pair((s,r): (i8,usize)) -> u32 {
+    return r as u32 | if s < 0 { 1 << 31 } else { 0 };
+}
+
+pub fn unpack_pair(p: u32) -> (i8,usize) {
+    return (if p >> 31 == 1 { -1 } else { 1 }, (p & !(1 << 31)) as usize);
+}
+
+// All relations stored per triple. Each relation is stored once for every one
+// of its k-2 triples, so this is only viable while C(n,k) is moderate.
+pub struct Materialized {
+    rels: Vec<Vec<u32>>,
+}
+
+impl Materialized {
+    pub fn new(n: usize, all_rels: Vec<Rel>) -> Materialized {
+        let mut rels: Vec<Vec<u32>> = vec![vec![]; ntriples(n)];
+        for rel in all_rels {
+            for (_,t) in rel.iter() { rels[*t].extend(rel.iter().map(|p| pack_pair(*p))); }
+        }
+        return Materialized { rels };
+    }
+
+    pub fn at(&self, t: usize) -> &[u32] {
+        return &self.rels[t];
+    }
+}
+
+impl Relations for Materialized {
+    fn involving<'a>(&'a self, t: usize, buf: &'a mut Vec<(i8,usize)>) -> &'a [(i8,usize)] {
+        buf.clear();
+        buf.extend(self.at(t).iter().map(|p| unpack_pair(*p)));
+        return buf;
+    }
+
+    fn packed(&self, t: usize) -> Option<&[u32]> {
+        return Some(self.at(t));
+    }
+}
+
+// Relations generated when asked for, from the k-subsets of 1..n containing
+// the triple. Uses no memory, at the cost of enumerating C(n-3,k-3) subsets
+// every time. A relation involves abc when a, b and c are consecutive on one
+// of its chains, that is when the points between a and c other than b are on
+// the other chain, so only those masks are generated.
+pub struct Lazy {
+    pub k: usize,
+    pub n: usize,
+}
+
+impl Relations for Lazy {
+    fn involving<'a>(&'a self, t: usize, buf: &'a mut Vec<(i8,usize)>) -> &'a [(i8,usize)] {
+        let (a,b,c) = uncd(t);
+        buf.clear();
+        for pts in containing(self.n, self.k, &[a,b,c]) {
+            // the bits of the inner points on the chain of b, on the other
+            // one, and on either.
+            let (mut same, mut other, mut free) = (0, 0, 0);
+            for (i,p) in pts[2..self.k-1].iter().enumerate() {
+                if [a,b,c].contains(p) { same |= 1 << i; }
+                else if a < *p && *p < c { other |= 1 << i; }
+                else { free |= 1 << i; }
+            }
+            // pts[1] is on the chain of the bits set.
+            let p = pts[1];
+            let sides = if [a,b,c].contains(&p) { &[same][..] } else if a < p && p < c { &[other][..] } else { &[same,other][..] };
+            for side in sides {
+                let mut s = free;
+                loop {
+                    push_relation(&pts, side | s, buf);
+                    if s == 0 { break; }
+                    s = (s-1) & free;
+                }
+            }
+        }
+        return buf;
+    }
+}
+
+// Stored pairs, counting every copy of a relation, above which Lazy is used.
+const MAX_MATERIALIZED: usize = 1 << 26;
+
+// The provider for k-gons on n points: Materialized if it fits, Lazy otherwise.
+pub fn provider(k: usize, n: usize) -> Box<dyn Relations> {
+    let subsets = (1..=k).fold(1_usize, |c,i| c.saturating_mul(n+1-i) / i);
+    if subsets.saturating_mul(1 << (k-3)).saturating_mul((k-2)*(k-2)) > MAX_MATERIALIZED {
+        return Box::new(Lazy { k, n });
+    }
+    return Box::new(Materialized::new(n, all_relations(k, n)));
+}
@@ -0,0 +1,155 @@
+use std::fmt;
+use std::path::Path;
+use crate::args::{Args, die};
+use crate::comb::{cd, ntriples, tuples};
+use crate::omega::{Omega, compatible};
+use crate::quads::VALID;
+use crate::relations::convex_relations;
+
+// The largest k whose C(k,3) triples fit in the u64 index.
+pub const MAX_K: usize = 8;
+
+// A signature of a window of k points: an orientation for each triple, packed as
+// its index (see omega).
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct Signature {
+    pub k: usize,
+    pub index: u64,
+}
+
+impl Signature {
+    pub fn from_index(k: usize, index: u64) -> Signature {
+        return Signature { k, index };
+    }
+
+    pub fn from_values(k: usize, v: &[i8]) -> Signature {
+        return Signature { k, index: v.iter().fold(0, |x,y| (x << 1) | (*y == 1) as u64) };
+    }
+
+    // Reads a signature written as a string of C(k,3) signs, as "+--+...",
+    // finding k from its length.
+    pub fn parse(s: &str) -> Result<Signature, String> {
+        let v = s.chars().map(|c| match c {
+            '+' => Ok(1),
+            '-' => Ok(-1),
+            _ => Err(format!("invalid sign {}", c)),
+        }).collect::<Result<Vec<i8>, String>>()?;
+        let k = (3..=MAX_K).find(|k| ntriples(*k) == v.len())
+            .ok_or(format!("{} signs is not C(k,3) for any k <= {}", v.len(), MAX_K))?;
+        return Ok(Signature::from_values(k, &v));
+    }
+
+    pub fn len(&self) -> usize {
+        return ntriples(self.k);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.len() == 0;
+    }
+
+    // value of the triple with cd = i.
+    pub fn get(&self, i: usize) -> i8 {
+        return match (self.index >> (self.len() - 1 - i)) & 1 {1 => 1, _ => -1};
+    }
+
+    pub fn values(&self) -> Vec<i8> {
+        return (0..self.len()).map(|i| self.get(i)).collect();
+    }
+
+    pub fn signs(&self) -> String {
+        return self.values().iter().map(|x| if *x == 1 { '+' } else { '-' }).collect();
+    }
+
+    // The signature of the points reflected by x -> -x. Point i becomes k+1-i,
+    // and since reversing a triple flips its orientation again, values are kept.
+    pub fn mirror(&self) -> Signature {
+        let k = self.k;
+        let mut v = vec![0; self.len()];
+        for [a,b,c] in tuples::<3>(k) {
+            v[cd(k+1-c,k+1-b,k+1-a)] = self.get(cd(a,b,c));
+        }
+        return Signature::from_values(k, &v);
+    }
+
+    // The signature of the points reflected by y -> -y.
+    pub fn negate(&self) -> Signature {
+        return Signature::from_values(self.k, &self.values().iter().map(|x| -x).collect::<Vec<i8>>());
+    }
+
+    // The orbit under mirror and negate, with the name of each symmetry.
+    pub fn orbit(&self) -> Vec<(&'static str, Signature)> {
+        return vec![("identity", *self), ("mirror", self.mirror()),
+                    ("negate", self.negate()), ("mirror negate", self.mirror().negate())];
+    }
+}
+
+// A triangle table with a row for each pair (a,b) and a column for each c.
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "    ")?;
+        for c in 3..=self.k { write!(f, " {:>2}", c)?; }
+        writeln!(f)?;
+        for [a,b] in tuples::<2>(self.k-1) {
+            write!(f, "{:>2}{:<2}", a, b)?;
+            for c in 3..=self.k {
+                if c <= b { write!(f, "   ")?; }
+                else { write!(f, "  {}", if self.get(cd(a,b,c)) == 1 { '+' } else { '-' })?; }
+            }
+            writeln!(f)?;
+        }
+        return Ok(());
+    }
+}
+
+// he sig 983040 [--k 6] [--n 17], or he sig +-+-...
+// The signature given on the command line, either by its index as a signature
+// of k points or as a string of signs.
+pub fn from_arg(arg: &str, k: usize) -> Signature {
+    if !(3..=MAX_K).contains(&k) { die(&format!("need 3 <= k <= {}", MAX_K)); }
+    return match arg.parse::<u64>() {
+        Ok(i) if i < 1 << ntriples(k) => Signature::from_index(k, i),
+        Ok(_) => die(&format!("{} is not the index of a signature of {} points", arg, k)),
+        Err(_) => Signature::parse(arg).unwrap_or_else(|e| die(&e)),
+    };
+}
+
+// For every quadrilateral of s its points, its signs and whether it is convex,
+// has one point inside, or is not in VALID.
+pub fn quadrilaterals(s: &Signature) -> Vec<String> {
+    return tuples::<4>(s.k).map(|[a,b,c,d]| {
+        let ts = [cd(a,b,c),cd(a,b,d),cd(a,c,d),cd(b,c,d)];
+        let q: String = ts.iter().map(|i| if s.get(*i) == 1 { '+' } else { '-' }).collect();
+        let convex = convex_relations(&[a,b,c,d]).iter().any(|rel| {
+            let (s0,t0) = rel[0];
+            rel.iter().all(|(x,t)| x*s.get(*t) == s0*s.get(t0))
+        });
+        let label = if !VALID.contains(&(s.get(ts[0]),s.get(ts[1]),s.get(ts[2]),s.get(ts[3]))) { "invalid" }
+            else if convex { "convex" } else { "one point inside" };
+        format!("({},{},{},{}) {} {}", a, b, c, d, q, label)
+    }).collect();
+}
+
+pub fn main(args: &Args) {
+    let n = args.num("n", 17);
+    let s = from_arg(args.rest.first().unwrap_or_else(|| die("missing signature")), args.num("k", 6));
+    let k = s.k;
+    if k < 4 || n < k { die("need 4 <= k <= n"); }
+    let path = format!("omega{}.bin", k);
+    let omega = Omega::cached(k, n-k+1, true, Path::new(&path)).unwrap_or_else(|e| die(&format!("{}: {}", path, e)));
+    println!("signature {} of {} points: {}", s.index, k, s.signs());
+    print!("{}", s);
+    match omega.id(s.index) {
+        None => println!("not in omega"),
+        Some(id) => {
+            let successors = compatible(k, &omega.sigs)[id].len();
+            println!("in omega, {} compatible successors, {} of them on chains of {} windows, {} such chains",
+                     successors, omega.comp[id].len(), n-k+1, omega.chains[id]);
+        }
+    }
+    println!("orbit:");
+    for (name, t) in s.orbit() {
+        println!("  {:<14} {:>8} {}{}", name, t.index, t.signs(), if omega.id(t.index).is_some() { "" } else { "  (not in omega)" });
+    }
+    println!("quadrilaterals:");
+    for line in quadrilaterals(&s) { println!("  {}", line); }
+}
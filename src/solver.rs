@@ -0,0 +1,440 @@
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+use crate::comb::subsets;
+use crate::holes;
+use crate::quads::{VALID, fillquad};
+use crate::quints::{self, Fill, NTRIPLES};
+use crate::relations::unpack_pair;
+use crate::tables::Tables;
+
+// Search for an assignment of orientations to the triples of points 1..n with
+// no convex k-gon, following the algorithm of "Computer solution to the
+// 17-point Erdős-Szekeres problem" for any k and n.
+
+// Given some assignment f, and a list of the recent assigned indexes, it checkes
+// weather a contradiction has been reached. And recursively keeps assigning elements
+// if they are forced to avoid satisfying a convex relation, or to keep the geometric
+// conditions all satisfied. See Step 1 of the algorithm in the paper.
+pub fn add(t: &Tables, f: &mut Vec<i8>, hs: &mut Vec<usize>, added: &Vec<usize>) -> bool {
+    if added.is_empty() { return true }
+    let mut toadd: Vec<(usize,i8)> = Vec::new();
+    let mut buf = Vec::new();
+    for cod in added.iter() {
+        match t.rels.packed(*cod) {
+            Some(p) => for rel in p.chunks_exact(t.k-2) {
+                if !relation(rel.iter().map(|p| unpack_pair(*p)), f, &mut toadd) { return false; }
+            },
+            None => for rel in t.relations(*cod, &mut buf) {
+                if !relation(rel.iter().copied(), f, &mut toadd) { return false; }
+            },
+        }
+        for (x,y,z,w) in t.quads[*cod].iter() {
+            let (a,b,c,d) = (f[*x],f[*y],f[*z],f[*w]);
+            if !VALID.contains(&(a,b,c,d)) { return false; }
+            if !t.fill { continue; }
+            match fillquad((a,b,c,d)) {
+                None => continue,
+                Some((x1,y1,z1,w1)) => {
+                    if a == 0 { toadd.push((*x,x1)); }
+                    if b == 0 { toadd.push((*y,y1)); }
+                    if c == 0 { toadd.push((*z,z1)); }
+                    if d == 0 { toadd.push((*w,w1)); }
+                }
+            }
+        }
+        // a chain is there when every f[r] equals its sign, so if all of them
+        // but one do, that one is forced to the opposite.
+        for c in t.chains.iter() {
+            'c: for ch in c.involving(*cod) {
+                let mut free = None;
+                for (s,r) in ch.iter().map(|p| unpack_pair(*p)) {
+                    if f[r] == 0 {
+                        if free.is_some() { continue 'c; }
+                        free = Some((s,r));
+                    } else if f[r] != s { continue 'c; }
+                }
+                match free {
+                    None => return false,
+                    Some((s,r)) => toadd.push((r,-s)),
+                }
+            }
+        }
+        // a hole is there when its relation has some value x and every point
+        // between is outside. If a single condition is left and it is a single
+        // triple, that triple is forced the other way.
+        if let Some(h) = &t.holes {
+            for i in h.at[*cod].iter() {
+                for x in [1,-1] {
+                    match holes::pending(&h.all[*i as usize], f, x) {
+                        None => {}
+                        Some((rel, qs)) => match (rel.as_slice(), qs.as_slice()) {
+                            ([], []) => return false,
+                            ([(s,r)], []) => toadd.push((*r,-s*x)),
+                            ([], [q]) if q.len() == 1 => toadd.push((q[0].1,-q[0].0*x)),
+                            _ => {}
+                        },
+                    }
+                }
+            }
+        }
+        if let Some(q) = &t.quints {
+            for p in q.at[*cod].iter() {
+                match q.fill[quints::index(f, p)] {
+                    Fill::Invalid => return false,
+                    Fill::Open => {}
+                    Fill::Forced(s) => for (i,x) in p.iter().enumerate() {
+                        if f[*x] == 0 { toadd.push((*x, if (s >> (NTRIPLES-1-i)) & 1 == 1 { 1 } else { -1 })); }
+                    },
+                }
+            }
+        }
+    }
+    let mut toaddi = Vec::new();
+    for (x,v) in toadd.iter() {
+        let val = f[*x];
+        if val == 0 {
+            f[*x] = *v;
+            hs.push(*x);
+            toaddi.push(*x);
+        }
+        else if *v != val { return false; }
+    }
+    return add(t, f, hs, &toaddi);
+}
+
+// a relation is satisfied when every sign*f[r] equals the same x. If all of
+// them but one agree, that one is forced to the opposite of x. Returns false
+// if the relation is satisfied.
+fn relation(rel: impl Iterator<Item = (i8,usize)>, f: &[i8], toadd: &mut Vec<(usize,i8)>) -> bool {
+    let (mut x, mut free) = (0, None);
+    for (s,r) in rel {
+        let y = s*f[r];
+        if y == 0 {
+            if free.is_some() { return true; }
+            free = Some((s,r));
+        } else if x == 0 { x = y; }
+        else if x != y { return true; }
+    }
+    match free {
+        None => return false,
+        Some((s,r)) => if x != 0 { toadd.push((r,-s*x)); }
+    }
+    return true;
+}
+
+// Sets the relations involved in u_j to a particular state given by the packed v.
+pub fn set_u(t: &Tables, f: &mut Vec<i8>, hs: &mut Vec<usize>, j: usize, v: u64) -> bool {
+    let mut toadd = Vec::new();
+    for (i,cod) in t.window(j).iter().enumerate() {
+        let val = f[*cod];
+        let x = t.omega.get(v,i);
+        if val == 0 {
+            toadd.push(*cod);
+            f[*cod] = x;
+            hs.push(*cod);
+        } else if val != x { return false; }
+    }
+    return add(t, f, hs, &toadd);
+}
+
+// returns the state of relations involved in u_j as a pair of packed masks:
+// the assigned triples, and among them the ones assigned to 1.
+pub fn get_u(t: &Tables, f: &[i8], j: usize) -> (u64,u64) {
+    let (mut set, mut pos) = (0,0);
+    for cod in t.window(j).iter() {
+        set <<= 1;
+        pos <<= 1;
+        if f[*cod] != 0 { set |= 1; }
+        if f[*cod] == 1 { pos |= 1; }
+    }
+    return (set,pos);
+}
+
+// given an assignment f and the information of assigned elements in hs
+// it restores f to when it had k elements assigned. This assumes that
+// hs has been faithfully filled.
+pub fn restore(f: &mut Vec<i8>, hs: &mut Vec<usize>, k: usize) {
+    for i in k..hs.len() { f[hs[i]] = 0; }
+    for _ in 0..hs.len() - k { hs.pop(); }
+}
+
+// An assignment packed in 2 bits per triple: 0 unassigned, 1 for 1 and 2 for
+// -1, four triples per byte from the low bits.
+pub fn pack(f: &[i8]) -> Vec<u8> {
+    let mut p = vec![0; f.len().div_ceil(4)];
+    for (i,x) in f.iter().enumerate() {
+        p[i/4] |= match x { 1 => 1, -1 => 2, _ => 0 } << (2*(i%4));
+    }
+    return p;
+}
+
+pub fn unpack(p: &[u8], nt: usize) -> Vec<i8> {
+    return (0..nt).map(|i| match (p[i/4] >> (2*(i%4))) & 3 { 1 => 1, 2 => -1, _ => 0 }).collect();
+}
+
+// Performs the r-bit-check described in page 10 (there for r = 1, 2, 3, here
+// for any r). Returns the first r unassigned triples such that every way of
+// assigning them leads to a contradiction, if there are any.
+pub fn bit_check(t: &Tables, f: &mut Vec<i8>, hs: &mut Vec<usize>, r: usize) -> Option<Vec<usize>> {
+    let k = hs.len();
+    let free: Vec<usize> = (0..t.ntriples()).filter(|i| f[*i] == 0).collect();
+    'p: for s in subsets(free.len(), r) {
+        let probe: Vec<usize> = s.iter().map(|i| free[i-1]).collect();
+        for signs in 0..1 << r {
+            for (b,x) in probe.iter().enumerate() {
+                hs.push(*x);
+                f[*x] = if (signs >> (r-1-b)) & 1 == 0 { 1 } else { -1 };
+            }
+            let b = add(t,f,hs,&probe);
+            restore(f,hs,k);
+            if b { continue 'p; }
+        }
+        return Some(probe);
+    }
+    return None;
+}
+
+// The first window u_j with some unassigned triple, if any.
+pub fn open_window(t: &Tables, f: &[i8]) -> Option<usize> {
+    return (1..=t.nwindows()).find(|j| get_u(t,f,*j).0 != t.full());
+}
+
+// The signatures that may be assigned to the open window u_j: compatible with
+// u_{j-1}, lying on a chain long enough to reach u_{n-k+1}, and agreeing with
+// the triples of u_j already assigned.
+pub fn candidates<'a>(t: &'a Tables, f: &[i8], j: usize) -> impl Iterator<Item = u64> + 'a {
+    let omega = &t.omega;
+    let prev = omega.id(get_u(t,f,j-1).1).unwrap();
+    let (set,pos) = get_u(t,f,j);
+    let rest = t.nwindows()-j;
+    return omega.comp[prev].iter().map(|i| *i as usize)
+        .filter(move |i| omega.reach[*i] > rest && (omega.sigs[*i] ^ pos) & set == 0)
+        .map(|i| omega.sigs[i]);
+}
+
+// How a run reports its progress on stderr, and when it gives up.
+#[derive(Clone, Copy, Default)]
+pub struct Limits {
+    pub progress: Option<Duration>,
+    pub max_nodes: Option<u64>,
+    pub max_time: Option<Duration>,
+}
+
+// The state of a run of search for one signature: how many nodes (calls to
+// search) and leaves (nodes with every window assigned) it visited, how many of
+// the leaves passed the r-bit-check for r = 1, 2, 3, and the position of the
+// node being visited, as (index, number of candidates) at each depth. A run
+// which stopped at its limits can be resumed from its checkpoint, that is the
+// counters, stages and the indexes of the path to the node it did not visit
+// (stage1 is not in it, but holds passed[0] leaves to carry over). A run
+// with a prefix only searches the subtree where the signatures chosen at the
+// first nodes of the path (for u_2, u_3, .. unless propagation fills some
+// window) are the ones of the prefix. Leaves go through the bit checks up to
+// the r-bit-check with r = stages, and if stage1 is given the leaves passing
+// the one-bit-check are kept there, packed (see pack).
+pub struct Run {
+    pub v: u64,
+    pub prefix: Vec<u64>,
+    pub stages: usize,
+    pub stage1: Option<Vec<Vec<u8>>>,
+    pub nodes: u64,
+    pub leaves: u64,
+    pub passed: [u64;3],
+    pub stopped: bool,
+    path: Vec<(usize,usize)>,
+    resume: Vec<usize>,
+    limits: Limits,
+    before: Duration,
+    nodes0: u64,
+    start: Instant,
+    last: Instant,
+}
+
+impl Run {
+    pub fn new(v: u64, limits: Limits) -> Run {
+        let now = Instant::now();
+        return Run { v, prefix: Vec::new(), stages: 3, stage1: None, nodes: 0, leaves: 0, passed: [0;3], stopped: false, path: Vec::new(), resume: Vec::new(),
+                     limits, before: Duration::ZERO, nodes0: 0, start: now, last: now };
+    }
+
+    // A run continuing the one that gave the checkpoint c.
+    pub fn resume(c: &str, limits: Limits) -> Result<Run, String> {
+        let bad = || format!("invalid checkpoint {}", c);
+        let mut w: Vec<&str> = c.split_whitespace().collect();
+        // checkpoints from before stages was kept in them ran all three.
+        if w.len() == 8 { w.insert(6, "3"); }
+        if w.len() != 9 { return Err(bad()); }
+        let num = |i: usize| w[i].parse::<u64>().map_err(|_| bad());
+        let mut run = Run::new(num(0)?, limits);
+        (run.nodes, run.leaves, run.passed) = (num(1)?, num(2)?, [num(3)?, num(4)?, num(5)?]);
+        run.stages = num(6)? as usize;
+        if !(1..=3).contains(&run.stages) { return Err(bad()); }
+        run.before = Duration::from_secs_f64(w[7].parse().map_err(|_| bad())?);
+        run.nodes0 = run.nodes;
+        if w[8] != "-" {
+            run.resume = w[8].split('.').map(|i| i.parse().map_err(|_| bad())).collect::<Result<Vec<usize>, String>>()?;
+        }
+        return Ok(run);
+    }
+
+    // v, nodes, leaves, passed, stages, seconds and the path, as "3.0.17" ("-"
+    // if empty). A resumed run stopped on its way back down to its resume point
+    // keeps the rest of that path.
+    pub fn checkpoint(&self) -> String {
+        let mut path: Vec<usize> = self.path.iter().map(|(i,_)| *i).collect();
+        path.extend(self.resume.iter().skip(path.len()));
+        let path = if path.is_empty() { "-".to_string() }
+                   else { path.iter().map(|i| i.to_string()).collect::<Vec<String>>().join(".") };
+        return format!("{} {} {} {} {} {} {} {:.3} {}", self.v, self.nodes, self.leaves,
+                       self.passed[0], self.passed[1], self.passed[2], self.stages, self.elapsed().as_secs_f64(), path);
+    }
+
+    // Adds the counts and time of other, a run of another part of the tree.
+    pub fn merge(&mut self, other: &Run) {
+        self.nodes += other.nodes;
+        self.leaves += other.leaves;
+        for r in 0..3 { self.passed[r] += other.passed[r]; }
+        self.before += other.elapsed();
+    }
+
+    // Assignments that survived every check.
+    pub fn survivors(&self) -> u64 {
+        return self.passed[self.stages-1];
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        return self.before + self.start.elapsed();
+    }
+
+    // Fraction of the search tree already done, assuming that the subtrees
+    // of the candidates of a node are all equally expensive.
+    pub fn done(&self) -> f64 {
+        let (mut frac, mut w) = (0.0, 1.0);
+        for (i,m) in self.path.iter() {
+            w /= *m as f64;
+            frac += *i as f64 * w;
+        }
+        return frac;
+    }
+
+    // Counts a node, unless the run has to stop before visiting it. The nodes
+    // above the resume point were counted by the run that stopped there.
+    fn visit(&mut self) -> bool {
+        let l = self.limits;
+        if l.max_nodes.is_some_and(|m| self.nodes - self.nodes0 >= m) || l.max_time.is_some_and(|m| self.start.elapsed() >= m) {
+            self.stopped = true;
+            return false;
+        }
+        if self.path.len() < self.resume.len() { return true; }
+        self.nodes += 1;
+        if let Some(every) = l.progress {
+            if self.last.elapsed() >= every {
+                self.last = Instant::now();
+                self.report();
+            }
+        }
+        return true;
+    }
+
+    // One line, written at once so that the lines of parallel runs do not mix.
+    fn report(&self) {
+        let (done, secs) = (self.done(), self.elapsed().as_secs_f64());
+        let eta = if done > 0.0 { hours(secs * (1.0 - done) / done) } else { "?".to_string() };
+        let top = self.path.first().map(|(i,m)| format!("{}/{}", i, m)).unwrap_or_default();
+        let line = format!("progress idx: {}  nodes: {}  j: {}  top: {}  leaves: {}  passed: {} {} {}  done: {:.4}  eta: {}\n",
+                           self.v, self.nodes, self.path.len() + 1, top, self.leaves,
+                           self.passed[0], self.passed[1], self.passed[2], done, eta);
+        let _ = io::stderr().lock().write_all(line.as_bytes());
+    }
+}
+
+fn hours(secs: f64) -> String {
+    return match secs {
+        s if s < 60.0 => format!("{:.0}s", s),
+        s if s < 3600.0 => format!("{:.1}m", s / 60.0),
+        s => format!("{:.2}h", s / 3600.0),
+    };
+}
+
+// It attempts to assign every triplet involved in some u_j without satisfying none
+// of the convex relations, and all of the geometric ones.
+// It performs other types of checks if it assigned all triplets involved in
+// u_j for all j in 1..=n-k+1.
+// This assumes triplets in u_1 are already assigned.
+// When the run stops, it returns leaving in run.path the node it did not visit.
+pub fn search(t: &Tables, f: &mut Vec<i8>, hs: &mut Vec<usize>, run: &mut Run) {
+    if !run.visit() { return; }
+    match open_window(t,f) {
+        None => {
+            run.leaves += 1;
+            for r in 1..=run.stages {
+                if bit_check(t,f,hs,r).is_some() { return; }
+                run.passed[r-1] += 1;
+                if r == 1 {
+                    if let Some(s) = run.stage1.as_mut() { s.push(pack(f)); }
+                }
+            }
+        }
+        Some(j) => {
+            let d = run.path.len();
+            let cands: Vec<u64> = match run.prefix.get(d) {
+                None => candidates(t,f,j).collect(),
+                Some(u) => candidates(t,f,j).filter(|v| v == u).collect(),
+            };
+            let from = run.resume.get(d).copied().unwrap_or(0);
+            run.path.push((0, cands.len()));
+            for (i,v) in cands.into_iter().enumerate().skip(from) {
+                run.path[d].0 = i;
+                let k = hs.len();
+                let b = set_u(t,f,hs,j,v);
+                if b { search(t,f,hs,run); }
+                restore(f,hs,k);
+                if run.stopped { return; }
+                run.resume.truncate(d);
+            }
+            run.path.pop();
+        }
+    }
+}
+
+// The prefixes of the nodes d levels below the node given by the prefix from in
+// the search tree of v (the signatures assigned to u_2, u_3, ..), and of its
+// leaves above them.
+pub fn prefixes(t: &Tables, v: u64, from: &[u64], d: usize) -> Vec<Vec<u64>> {
+    fn descend(t: &Tables, f: &mut Vec<i8>, hs: &mut Vec<usize>, d: usize, prefix: &mut Vec<u64>, out: &mut Vec<Vec<u64>>) {
+        let j = match open_window(t,f) {
+            Some(j) if prefix.len() < d => j,
+            _ => { out.push(prefix.clone()); return; }
+        };
+        for u in candidates(t,f,j).collect::<Vec<u64>>() {
+            let k = hs.len();
+            if set_u(t,f,hs,j,u) {
+                prefix.push(u);
+                descend(t,f,hs,d,prefix,out);
+                prefix.pop();
+            }
+            restore(f,hs,k);
+        }
+    }
+    let mut out = Vec::new();
+    let mut f: Vec<i8> = vec![0;t.ntriples()];
+    let mut hs: Vec<usize> = Vec::new();
+    if !set_u(t,&mut f,&mut hs,1,v) { return out; }
+    for u in from.iter() {
+        match open_window(t,&f) {
+            Some(j) if set_u(t,&mut f,&mut hs,j,*u) => {}
+            _ => return out,
+        }
+    }
+    descend(t,&mut f,&mut hs,from.len()+d,&mut from.to_vec(),&mut out);
+    return out;
+}
+
+// Runs the search with the signature of run in u_1, from its checkpoint if it
+// is resumed.
+pub fn try_signature(t: &Tables, run: &mut Run) {
+    let mut f: Vec<i8> = vec![0;t.ntriples()];
+    let mut history: Vec<usize> = Vec::new();
+    let b = set_u(t,&mut f,&mut history,1,run.v);
+    if b { search(t,&mut f,&mut history,run); }
+}
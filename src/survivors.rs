@@ -0,0 +1,71 @@
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use rayon::prelude::*;
+use crate::args::{Args, die};
+use crate::binfile::{invalid, read_u32, read_u64, write_u32, write_u64};
+use crate::comb::ntriples;
+use crate::solver::{add, bit_check, unpack};
+use crate::tables::{self, Tables};
+
+// Files of the leaves of a signature that passed the one-bit-check, to run the
+// later checks on them offline. A file holds magic, version, k, n, the
+// signature, and then the assignments packed by solver::pack, one after the
+// other until the end of the file.
+
+const MAGIC: &[u8;8] = b"HESTAGE1";
+const VERSION: u32 = 1;
+
+pub fn save(path: &Path, k: usize, n: usize, v: u64, recs: &[Vec<u8>]) -> io::Result<()> {
+    let mut w = BufWriter::new(fs::File::create(path)?);
+    w.write_all(MAGIC)?;
+    write_u32(&mut w, VERSION)?;
+    write_u32(&mut w, k as u32)?;
+    write_u32(&mut w, n as u32)?;
+    write_u64(&mut w, v)?;
+    for r in recs.iter() { w.write_all(r)?; }
+    return w.flush();
+}
+
+// k, n, the signature and the assignments of a file written by save.
+pub fn load(path: &Path) -> io::Result<(usize, usize, u64, Vec<Vec<i8>>)> {
+    let bytes = fs::read(path)?;
+    if bytes.len() < MAGIC.len() || &bytes[..MAGIC.len()] != MAGIC { return Err(invalid("not a stage 1 file")); }
+    let mut r = &bytes[MAGIC.len()..];
+    if read_u32(&mut r)? != VERSION { return Err(invalid("unsupported stage 1 file version")); }
+    let (k, n) = (read_u32(&mut r)? as usize, read_u32(&mut r)? as usize);
+    let v = read_u64(&mut r)?;
+    let nt = ntriples(n);
+    let size = nt.div_ceil(4);
+    if r.len() % size != 0 { return Err(invalid("truncated assignment")); }
+    return Ok((k, n, v, r.chunks_exact(size).map(|p| unpack(p, nt)).collect()));
+}
+
+// he stages <file>.. [--to 3] [--threads 1]: runs the r-bit-checks for r = 2..=to
+// on the assignments of the files, printing for each file how many passed each
+// check, as results.txt does. The one-bit-check is run again first, so that
+// with --axioms 5 the first count is how many of the leaves survive it when the
+// conditions on five points are added.
+pub fn main(args: &Args) {
+    if args.rest.is_empty() { die("missing stage 1 files"); }
+    let to = args.num("to", 3);
+    rayon::ThreadPoolBuilder::new().num_threads(args.num("threads", 1)).build_global().unwrap();
+    let mut cached: Option<Tables> = None;
+    for p in args.rest.iter() {
+        let (k, n, v, recs) = load(Path::new(p)).unwrap_or_else(|e| die(&format!("{}: {}", p, e)));
+        let t = match cached.take() {
+            Some(t) if (k, n) == (t.k, t.n) => t,
+            _ => tables::from_args(args, k, n),
+        };
+        // the last check each assignment passed, 0 for none.
+        let last: Vec<usize> = recs.into_par_iter().map(|mut f| {
+            let mut hs: Vec<usize> = (0..f.len()).filter(|i| f[*i] != 0).collect();
+            let set = hs.clone();
+            if !add(&t, &mut f, &mut hs, &set) || bit_check(&t, &mut f, &mut hs, 1).is_some() { return 0; }
+            return (2..=to).take_while(|r| bit_check(&t, &mut f, &mut hs, *r).is_none()).last().unwrap_or(1);
+        }).collect();
+        let passed: Vec<String> = (1..=to).map(|r| last.iter().filter(|l| **l >= r).count().to_string()).collect();
+        println!("{:>7} {}", v, passed.join(" "));
+        cached = Some(t);
+    }
+}
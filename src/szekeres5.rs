@@ -1,15 +1,11 @@
 use std::collections::BTreeMap;
-use itertools::Itertools;
+use crate::comb::{cd, tuples};
 
 // Algorithm of "Computer solution to the 17-point Erdős-Szekeres problem".
 
 type Crel = (i8,usize,usize,usize);
 type CompatibleRels = BTreeMap<Vec<i8>, Vec<Vec<i8>>>;
 
-fn cd(a: usize, b: usize, c: usize) -> usize {
-    return ((c-1)*(c-2)*(c-3))/6 + ((b-1)*(b-2))/2 + a -1;
-}
-
 fn convex_rels5p(a: usize, b: usize, c: usize, d: usize, e: usize) -> Vec<Crel> {
     return vec![(1, cd(a,b,c),cd(b,c,d),cd(c,d,e)), (-1,cd(a,b,c),cd(b,c,e),cd(a,d,e)),
                 (-1,cd(a,b,d),cd(b,d,e),cd(a,c,e)), (-1,cd(a,c,d),cd(c,d,e),cd(a,b,e))];
@@ -17,10 +13,10 @@ fn convex_rels5p(a: usize, b: usize, c: usize, d: usize, e: usize) -> Vec<Crel>
 
 fn convex_rels5(n: usize) -> Vec<Vec<Crel>> {
     let mut convexr: Vec<Vec<Crel>> = vec![vec![];n*(n-1)*(n-2)/6];
-    for (a,b,c,d) in (1..n+1).tuple_combinations::<(_,_,_,_)>() {
+    for [a,b,c,d] in tuples::<4>(n) {
         for e in d+1..n+1 {
             for (c,r1,r2,r3) in convex_rels5p(a,b,c,d,e) {
-                for i in vec![r1,r2,r3].iter() {
+                for i in [r1,r2,r3].iter() {
                     convexr[*i].push((c,r1,r2,r3));
                 }
             }
@@ -48,7 +44,7 @@ fn compatible(omega: & Vec<Vec<i8>>) -> CompatibleRels {
     for v in omega.iter() {
         let mut vec: Vec<Vec<i8>> = Vec::new();
         for u in omega.iter() {
-            if (1..5).tuple_combinations::<(_,_,_)>().all(|(a,b,c)| v[cd(a+1,b+1,c+1)] == u[cd(a,b,c)]) {
+            if tuples::<3>(4).all(|[a,b,c]| v[cd(a+1,b+1,c+1)] == u[cd(a,b,c)]) {
                 vec.push(u.to_vec());
             }
         }
@@ -70,12 +66,12 @@ fn check_concave(rels: & Vec<Vec<Crel>>, f: &mut Vec<i8>, cod: usize) -> bool {
 fn first_undef(n: usize, f: &mut Vec<i8>) -> Option<usize> {
     let mut j: usize = 1;
     while j+5-1 <= n {
-        for (a,b,c) in (1..5+1).tuple_combinations::<(_,_,_)>() {
+        for [a,b,c] in tuples::<3>(5) {
             if f[cd(a+j-1,b+j-1,c+j-1)] == 0 {
                 return Some(j);
             }
         }
-        j = j+1;
+        j += 1;
     }
     return None;
 }
@@ -99,7 +95,7 @@ fn add(rels: &Vec<Vec<Crel>>, f: &mut Vec<i8>, hs: &mut Vec<usize>, added: usize
 }
 
 fn set_vec5(rels: &Vec<Vec<Crel>>, f: &mut Vec<i8>, hs: &mut Vec<usize>, j: usize, v: &Vec<i8>) -> bool {
-    for (a,b,c) in (1..5+1).tuple_combinations::<(_,_,_)>() {
+    for [a,b,c] in tuples::<3>(5) {
         let cod = cd(a+j-1,b+j-1,c+j-1);
         if f[cod] == 0 {
             f[cod] = v[cd(a,b,c)];
@@ -117,44 +113,57 @@ fn set_vec5(rels: &Vec<Vec<Crel>>, f: &mut Vec<i8>, hs: &mut Vec<usize>, j: usiz
 
 fn get_vec5(f: &mut Vec<i8>, j: usize) -> Vec<i8> {
     let mut v = vec![0;10];
-    for (a,b,c) in (1..5+1).tuple_combinations::<(_,_,_)>() {
+    for [a,b,c] in tuples::<3>(5) {
         v[cd(a,b,c)] = f[cd(a+j-1,b+j-1,c+j-1)];
     }
     return v;
 }
 
-fn search(n: usize, rels: &Vec<Vec<Crel>>, comp: & CompatibleRels, f: &mut Vec<i8>, hs: &mut Vec<usize>, count: &mut i64) -> () {
-    let oj = first_undef(n,f);
-    if oj == None { *count = *count+1; }
-    else {
-        let j = oj.unwrap();
-        let com = comp.get(&get_vec5(f,j-1)).unwrap();
-        let u = get_vec5(f, j);
-        for v in com.iter() {
-            if (0..v.len()).all(|i| u[i] == 0 || u[i] == v[i]) {
-                let k = hs.len();
-                let b = set_vec5(rels, f, hs, j, v);
-                if b { search(n, rels, comp, f, hs, count); }
-                for i in k..hs.len() { f[hs[i]] = 0; }
-                for _ in 0..hs.len() - k { hs.pop(); }
+fn search(n: usize, rels: &Vec<Vec<Crel>>, comp: & CompatibleRels, f: &mut Vec<i8>, hs: &mut Vec<usize>, count: &mut i64) {
+    match first_undef(n,f) {
+        None => *count += 1,
+        Some(j) => {
+            let com = comp.get(&get_vec5(f,j-1)).unwrap();
+            let u = get_vec5(f, j);
+            for v in com.iter() {
+                if (0..v.len()).all(|i| u[i] == 0 || u[i] == v[i]) {
+                    let k = hs.len();
+                    let b = set_vec5(rels, f, hs, j, v);
+                    if b { search(n, rels, comp, f, hs, count); }
+                    for i in k..hs.len() { f[hs[i]] = 0; }
+                    for _ in 0..hs.len() - k { hs.pop(); }
+                }
             }
         }
     }
 }
 
-pub fn main(){
-    const N: usize = 9;
+fn count_from(n: usize, rels: &Vec<Vec<Crel>>, comp: &CompatibleRels, v: &Vec<i8>) -> i64 {
+    let mut count: i64 = 0;
+    let mut f: Vec<i8> = vec![0;n*(n-1)*(n-2)/6];
+    let mut history: Vec<usize> = vec![];
+    let b = set_vec5(rels, &mut f, &mut history, 1, v);
+    if b { search(n, rels, comp, &mut f, &mut history, &mut count); }
+    return count;
+}
+
+// Number of assignments of the windows of n points found by the search, over
+// the signatures of u_1 starting by 1.
+pub fn count(n: usize) -> i64 {
     let omega = concave5();
     let comp = compatible(& omega);
-    let convexr = convex_rels5(N);
-    let mut count: i64 = 0;
-    for v in omega.iter() {
-        if v[0] == 1 {
-            let mut f: Vec<i8> = vec![0;N*(N-1)*(N-2)/6];
-            let mut history: Vec<usize> = vec![];
-            let b = set_vec5(&convexr, &mut f, &mut history, 1, v);
-            if b { search(N, &convexr, &comp, &mut f, &mut history, &mut count); }
-        }
-    }
-    println!("{}",count);
+    let convexr = convex_rels5(n);
+    return omega.iter().filter(|v| v[0] == 1).map(|v| count_from(n, &convexr, &comp, v)).sum();
+}
+
+// The same for the signature v of u_1, its 10 triples in the order of cd.
+pub fn count_with(n: usize, v: &[i8]) -> i64 {
+    let omega = concave5();
+    if !omega.iter().any(|u| u == v) { return 0; }
+    return count_from(n, &convex_rels5(n), &compatible(&omega), &v.to_vec());
+}
+
+pub fn main(){
+    const N: usize = 9;
+    println!("{}",count(N));
 }
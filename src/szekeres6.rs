@@ -1,326 +1,123 @@
-use std::collections::BTreeMap;
-use itertools::Itertools;
-use std::time::Instant;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
 use rayon::prelude::*;
-
-type Crel = (i8,i8,usize,usize,usize,usize);
-type Quad = (usize,usize,usize,usize);
-type QuadV = (i8,i8,i8,i8);
-type CompatibleRels = BTreeMap<Vec<i8>, Vec<Vec<i8>>>;
-
-const N: usize = 17;
-// valid signatures for a quadrilateral (see (2.3)). The ones that include 0's
-// are the ones from which a valid signature in (2.3) can be reached.
-const VALID: [QuadV;65] = [(1,1,1,1),(0,1,1,1),(1,0,1,1),(1,1,0,1),(1,1,1,0),(0,0,1,1),(0,1,0,1),(0,1,1,0),(1,0,0,1),(1,0,1,0),(1,1,0,0),(1,0,0,0),(0,1,0,0),(0,0,1,0),(0,0,0,1),(0,0,0,0),(1,1,1,-1),(0,1,1,-1),(1,0,1,-1),(1,1,0,-1),(0,0,1,-1),(0,1,0,-1),(1,0,0,-1),(0,0,0,-1),(1,1,-1,-1),(0,1,-1,-1),(1,0,-1,-1),(1,1,-1,0),(0,0,-1,-1),(0,1,-1,0),(1,0,-1,0),(0,0,-1,0),(1,-1,-1,-1),(0,-1,-1,-1),(1,-1,0,-1),(1,-1,-1,0),(0,-1,0,-1),(0,-1,-1,0),(1,-1,0,0),(0,-1,0,0),(-1,-1,-1,-1),(-1,0,-1,-1),(-1,-1,0,-1),(-1,-1,-1,0),(-1,0,0,-1),(-1,0,-1,0),(-1,-1,0,0),(-1,0,0,0),(-1,-1,-1,1),(0,-1,-1,1),(-1,0,-1,1),(-1,-1,0,1),(0,0,-1,1),(0,-1,0,1),(-1,0,0,1),(-1,-1,1,1),(0,-1,1,1),(-1,0,1,1),(-1,-1,1,0),(0,-1,1,0),(-1,0,1,0),(-1,1,1,1),(-1,1,0,1),(-1,1,1,0),(-1,1,0,0)];
-
-// forced quadrilateral signatures. For example the ony valid signature
-// that extends (-1,0,0,-1) is (-1,-1,-1,-1).
-fn fillquad (xs: QuadV) -> Option<QuadV> {
-    match xs {
-        (-1,0,0,-1)  => Some((-1,-1,-1,-1)),
-        (0,0,-1,1)   => Some((-1,-1,-1,1)),
-        (0,0,1,-1)   => Some((1,1,1,-1)),
-        (0,-1,1,0)   => Some((-1,-1,1,1)),
-        (0,1,-1,0)   => Some((1,1,-1,-1)),
-        (-1,1,0,0)   => Some((-1,1,1,1)),
-        (1,-1,0,0)   => Some((1,-1,-1,-1)),
-        (1,0,0,1)    => Some((1,1,1,1)),
-        (-1,0,-1,-1) => Some((-1,-1,-1,-1)),
-        (-1,-1,0,-1) => Some((-1,-1,-1,-1)),
-        (0,-1,-1,1)  => Some((-1,-1,-1,1)),
-        (-1,0,-1,1)  => Some((-1,-1,-1,1)),
-        (-1,-1,1,0)  => Some((-1,-1,1,1)),
-        (0,-1,1,1)   => Some((-1,-1,1,1)),
-        (0,1,-1,-1)  => Some((1,1,-1,-1)),
-        (-1,1,0,1)   => Some((-1,1,1,1)),
-        (0,1,1,-1)   => Some((1,1,1,-1)),
-        (-1,1,1,0)   => Some((-1,1,1,1)),
-        (1,-1,0,-1)  => Some((1,-1,-1,-1)),
-        (1,-1,-1,0)  => Some((1,-1,-1,-1)),
-        (1,0,1,-1)   => Some((1,1,1,-1)),
-        (1,0,1,1)    => Some((1,1,1,1)),
-        (1,1,-1,0)   => Some((1,1,-1,-1)),
-        (1,1,0,1)    => Some((1,1,1,1)),
-        _            => None
-    }
-}
-
-// Practical bijection between {0,1,..,n(n-1)(n-2)/6 -1} and {(a,b,c) : 1 <= a < b < c <= n} forall n.
-fn cd(a: usize, b: usize, c: usize) -> usize {
-    return ((c-1)*(c-2)*(c-3))/6 + ((b-1)*(b-2))/2 + a -1;
-}
-
-// convex relations for an hexagon (see (4.1)).
-fn relations(a: usize, b: usize, c: usize, d: usize, e: usize, f: usize) -> Vec<Crel> {
-    return vec![( 1, 1,cd(a,b,c),cd(b,c,d),cd(c,d,e),cd(d,e,f)),
-                ( 1,-1,cd(a,b,c),cd(b,c,d),cd(c,d,f),cd(a,e,f)),
-                ( 1,-1,cd(a,b,c),cd(b,c,e),cd(c,e,f),cd(a,d,f)),
-                ( 1,-1,cd(a,b,d),cd(b,d,e),cd(d,e,f),cd(a,c,f)),
-                ( 1,-1,cd(a,c,d),cd(c,d,e),cd(d,e,f),cd(a,b,f)),
-                (-1,-1,cd(a,b,c),cd(b,c,f),cd(a,d,e),cd(d,e,f)),
-                (-1,-1,cd(a,b,d),cd(b,d,f),cd(a,c,e),cd(c,e,f)),
-                (-1,-1,cd(a,c,d),cd(c,d,f),cd(a,b,e),cd(b,e,f))];
-}
-
-// Returns a vector v such that v[cd(a,b,c)] has all the convex relations involving (a,b,c).
-fn convex_rels6() -> Vec<Vec<Crel>> {
-    let mut convexr: Vec<Vec<Crel>> = vec![vec![];N*(N-1)*(N-2)/6];
-    for (a,b,c,d) in (1..=N).tuple_combinations::<(_,_,_,_)>() {
-        for (e,f) in (d+1..=N) .tuple_combinations::<(_,_)>() {
-            for (x,y,r1,r2,r3,r4) in relations(a,b,c,d,e,f) {
-                for i in vec![r1,r2,r3,r4].iter() {
-                    convexr[*i].push((x,y,r1,r2,r3,r4));
-                }
-        }
-        }
+use crate::args::{Args, die};
+use crate::estimate;
+use crate::solver::{Limits, Run, pack, try_signature};
+use crate::survivors;
+use crate::tables::{self, Tables};
+
+// Algorithm of "Computer solution to the 17-point Erdős-Szekeres problem": every
+// signature of Omega which starts by 1 is tried for u_1 on 17 points.
+
+pub const N: usize = 17;
+
+// The line reporting a run: its count, or UNKNOWN with the counts so far and
+// its checkpoint if it stopped.
+pub fn result(t: &Tables, run: &Run) -> String {
+    let v = run.v;
+    let (count, elapsed_time) = (run.survivors(), run.elapsed());
+    if run.stopped {
+        return format!("idx: {}  UNKNOWN  nodes: {}  leaves: {}  passed: {} {} {}  t: {}  checkpoint: {}",
+                       v, run.nodes, run.leaves, run.passed[0], run.passed[1], run.passed[2],
+                       elapsed_time.as_secs() as f32/60.0, run.checkpoint());
     }
-    return convexr;
-}
-
-// All signatures for a hexagon which are concave and satisfy geometric conditions (Omega*).
-fn concave6() -> Vec<Vec<i8>> {
-    let mut vec: Vec<Vec<i8>> = Vec::new();
-    'x: for i in 0..2_usize.pow(20) {
-        let s = format!("{:020b}", i);
-        let v: Vec<i8> = s.chars().map(|c| match c {'0' => -1, _ => 1}).collect();
-        for (p,q,r1,r2,r3,r4) in relations(1,2,3,4,5,6) {
-            let (x1,x2,x3,x4) = (v[r1],v[r2],v[r3],v[r4]);
-            if x1 == x2 && x1 == p*x3 && x1 == q*x4 { continue 'x; }
-        }
-        for (a,b,c,d) in (1..=6).tuple_combinations::<(_,_,_,_)>() {
-            let quad = (v[cd(a,b,c)],v[cd(a,b,d)],v[cd(a,c,d)],v[cd(b,c,d)]);
-            if VALID.iter().all(|y| quad != *y) { continue 'x; }
-        }
-        vec.push(v)
-    }
-    return vec;
-}
-
-// By viewing each element in omega as a binary number (1's are 1's, -1's are 0's)
-// one can identify each one of them by a number which we call its index.
-fn signature_idx(v: &Vec<i8>) -> i32 {
-    return (0..v.len()).map(|i| match v[v.len()-i-1] {1 => 2_i32.pow(i as u32), _ => 0}).sum()
-}
-
-// Creates a Map which assigns for every v in Omega its compatible set of signatures.
-// The ones that can be assigned to u_{j+1} if v is assigned to u_{j}. See (3.4) and
-// the first note on efficiency at the end of page 8.
-fn compatible(omega: & Vec<Vec<i8>>) -> CompatibleRels {
-    let mut x = BTreeMap::new();
-    for v in omega.iter() {
-        let mut vec: Vec<Vec<i8>> = Vec::new();
-        for u in omega.iter() {
-            if (1..6).tuple_combinations::<(_,_,_)>().all(|(a,b,c)| v[cd(a+1,b+1,c+1)] == u[cd(a,b,c)]) {
-                vec.push(u.to_vec());
-            }
-        }
-        x.insert(v.to_vec(),vec);
-    }
-    return x;
-}
-
-// For each triple (a,b,c) quads[cd(a,b,c)] will store all quadrilateral
-// relations that involve a b and c.
-fn quadrilaterals() -> Vec<Vec<Quad>> {
-    let mut quads: Vec<Vec<Quad>> = vec![vec![];N*(N-1)*(N-2)/6];
-    for (a,b,c,d) in (1..=N).tuple_combinations::<(_,_,_,_)>() {
-        let (x,y,z,w) = (cd(a,b,c),cd(a,b,d),cd(a,c,d),cd(b,c,d));
-        quads[x].push((x,y,z,w));
-        quads[y].push((x,y,z,w));
-        quads[z].push((x,y,z,w));
-        quads[w].push((x,y,z,w));
-    }
-    return quads;
-}
-
-
-// ================= END OF PRE-PROCESSING INTENDED FUNCTIONS ======================
-
-// Given some assignment f, and a list of the recent assigned indexes, it checkes
-// weather a contradiction has been reached. And recursively keeps assigning elements
-// if they are forced to avoid satisfying a convex relation, or to keep the geometric
-// conditions all satisfied. See Step 1 of the algorithm in the paper.
-fn add(rels: &Vec<Vec<Crel>>, quads: & Vec<Vec<Quad>>, f: &mut Vec<i8>, hs: &mut Vec<usize>, added: &Vec<usize>) -> bool {
-    if added.is_empty() { return true }
-    let mut toadd: Vec<(usize,i8)> = Vec::new();
-    for cod in added.iter() {
-        for (p,q,r1,r2,r3,r4) in rels[*cod].iter() {
-            let (x1,x2,x3,x4) = (f[*r1],f[*r2],f[*r3],f[*r4]);
-            if x1 != 0 && x2 != 0 && x3 != 0 && x4 != 0 && (x1 == x2 && x1 == p*x3 && x1 == q*x4) {
-                return false;
-            }
-            if x2 != 0 && x3 != 0 && x4 != 0 && x2 == p*x3 && x2 == q*x4 { toadd.push((*r1,-x2)); }
-            if x1 != 0 && x3 != 0 && x4 != 0 && x1 == p*x3 && x1 == q*x4 { toadd.push((*r2,-x1)); }
-            if x1 != 0 && x2 != 0 && x4 != 0 && x1 == x2 && x1 == q*x4 { toadd.push((*r3,-p*x1)); }
-            if x1 != 0 && x2 != 0 && x3 != 0 && x1 == x2 && x1 == p*x3 { toadd.push((*r4,-q*x1)); }
-        }
-        for (x,y,z,w) in quads[*cod].iter() {
-            let (a,b,c,d) = (f[*x],f[*y],f[*z],f[*w]);
-            if VALID.iter().all(|y| (a,b,c,d) != *y) { return false; }
-            match fillquad((a,b,c,d)) {
-                None => continue,
-                Some((x1,y1,z1,w1)) => {
-                    if a == 0 { toadd.push((*x,x1)); }
-                    if b == 0 { toadd.push((*y,y1)); }
-                    if c == 0 { toadd.push((*z,z1)); }
-                    if d == 0 { toadd.push((*w,w1)); }
-                }
+    let chains = t.omega.id(v).map(|i| t.omega.chains[i]).unwrap_or(0);
+    return format!("idx: {}  n: {}  t: {}  chains: {}  v: {:?}",v,count,elapsed_time.as_secs() as f32/60.0,chains,t.omega.signature(v).values());
+}
+
+// Runs a signature, leaving the leaves that pass the one-bit-check in
+// dump/<idx>.s1 if dump is given. A stopped run leaves the ones found so far,
+// which its resumed run, with the same dump, takes over so that the file of
+// the finished run has them all. Returns whether some assignment survived
+// every check.
+fn run_signature(t: &Tables, mut run: Run, dump: Option<&str>) -> bool {
+    let path = dump.map(|d| Path::new(d).join(format!("{}.s1", run.v)));
+    if let Some(p) = &path {
+        let fail = |e: String| -> ! { die(&format!("{}: {}", p.display(), e)) };
+        let mut recs = Vec::new();
+        if run.passed[0] > 0 {
+            let (k, n, v, leaves) = survivors::load(p).unwrap_or_else(|e| fail(e.to_string()));
+            if (k, n, v) != (t.k, t.n, run.v) || leaves.len() as u64 != run.passed[0] {
+                fail(format!("not the {} stage 1 leaves of the checkpoint", run.passed[0]));
             }
+            recs = leaves.iter().map(|f| pack(f)).collect();
         }
+        run.stage1 = Some(recs);
     }
-    let mut toaddi = Vec::new();
-    for (x,v) in toadd.iter() {
-        let val = f[*x];
-        if val == 0 {
-            f[*x] = *v;
-            hs.push(*x);
-            toaddi.push(*x);
-        }
-        else if *v != val { return false; }
+    try_signature(t, &mut run);
+    if let (Some(p), Some(recs)) = (&path, &run.stage1) {
+        survivors::save(p, t.k, t.n, run.v, recs).unwrap_or_else(|e| die(&format!("{}: {}", p.display(), e)));
     }
-    return add(rels, quads, f, hs, &toaddi);
-}
-
-// Sets the relations involved in u_j to a particular state given by v.
-fn set_u(rels: &Vec<Vec<Crel>>, quads: & Vec<Vec<Quad>>,
-            f: &mut Vec<i8>, hs: &mut Vec<usize>, j: usize, v: &Vec<i8>) -> bool {
-    let mut toadd = Vec::new();
-    for (a,b,c) in (1..=6).tuple_combinations::<(_,_,_)>() {
-        let cod = cd(a+j-1,b+j-1,c+j-1);
-        let val = f[cod];
-        if val == 0 {
-            toadd.push(cod);
-            f[cod] = v[cd(a,b,c)];
-            hs.push(cod);
-        } else if val != v[cd(a,b,c)] { return false; }
+    println!("{}", result(t, &run));
+    return !run.stopped && run.survivors() > 0;
+}
+
+// --progress seconds between progress lines (0 for none), and the budget of
+// each signature: --max-nodes and --max-seconds.
+pub fn limits(args: &Args) -> Limits {
+    return Limits {
+        progress: match args.num("progress", 60) { 0 => None, s => Some(Duration::from_secs(s)) },
+        max_nodes: args.get("max-nodes").map(|_| args.num("max-nodes", 0)),
+        max_time: args.get("max-seconds").map(|_| Duration::from_secs_f64(args.num("max-seconds", 0.0))),
+    };
+}
+
+// The tables for k-gons on n points given by --k and --n, hexagons on 17 points
+// by default, for the commands sharing the signatures among processes.
+pub fn instance(args: &Args) -> Tables {
+    return tables::from_args(args, args.num("k", 6), args.num("n", N));
+}
+
+// The signatures to try for u_1: the ones starting by 1 on some chain of n-k+1
+// windows.
+pub fn signatures(t: &Tables) -> Vec<u64> {
+    let omega = &t.omega;
+    return omega.sigs.iter().enumerate().filter(|(i,v)| omega.get(**v,0) == 1 && omega.reach[*i] == t.nwindows())
+        .map(|(_,v)| *v).collect();
+}
+
+// Number of assignments surviving every check, over all the signatures to try.
+pub fn total(t: &Tables) -> u64 {
+    return signatures(t).iter().map(|v| {
+        let mut run = Run::new(*v, Limits::default());
+        try_signature(t, &mut run);
+        run.survivors()
+    }).sum();
+}
+
+// The signatures to try with some assignment surviving every check, sorted.
+pub fn satisfiable(t: &Tables) -> Vec<u64> {
+    return signatures(t).into_iter().filter(|v| {
+        let mut run = Run::new(*v, Limits::default());
+        try_signature(t, &mut run);
+        run.survivors() > 0
+    }).collect();
+}
+
+pub fn main(args: &Args) {
+    rayon::ThreadPoolBuilder::new().num_threads(args.num("threads", 1)).build_global().unwrap();
+    let t = instance(args);
+    if args.get("estimate").is_some() {
+        estimate::rank(&t, &signatures(&t), args.num("estimate", 100), args.num("seed", 1));
+        return;
     }
-    return add(rels, quads, f, hs, &toadd);
-}
-
-// returns a vector of the state of relations involved in u_j.
-fn get_u(f: &mut Vec<i8>, j: usize) -> Vec<i8> {
-    let mut v = vec![0;20];
-    for (a,b,c) in (1..=6).tuple_combinations::<(_,_,_)>() {
-        v[cd(a,b,c)] = f[cd(a+j-1,b+j-1,c+j-1)];
+    let limits = limits(args);
+    let dump = args.get("dump");
+    if let Some(d) = dump { fs::create_dir_all(d).unwrap_or_else(|e| die(&format!("{}: {}", d, e))); }
+    let stages = args.num("stages", 3);
+    if !(1..=3).contains(&stages) { die("--stages must be 1, 2 or 3"); }
+    if let Some(c) = args.get("resume") {
+        let run = Run::resume(c, limits).unwrap_or_else(|e| die(&e));
+        if args.get("stages").is_some() && run.stages != stages { die("the checkpoint has its own --stages"); }
+        run_signature(&t, run, dump);
+        return;
     }
-    return v;
-}
-
-// given an assignment f and the information of assigned elements in hs
-// it restores f to when it had k elements assigned. This assumes that
-// hs has been faithfully filled.
-fn restore(f: &mut Vec<i8>, hs: &mut Vec<usize>, k: usize) -> () {
-    for i in k..hs.len() { f[hs[i]] = 0; }
-    for _ in 0..hs.len() - k { hs.pop(); }
-}
-
-// Performs the one-bit-check described in page 10.
-fn one_bit_check(rels: &Vec<Vec<Crel>>, quads: & Vec<Vec<Quad>>,
-                 f: &mut Vec<i8>, hs: &mut Vec<usize>) -> bool {
-    let k = hs.len();
-    for i in 0..N*(N-1)*(N-2)/6 {
-        if f[i] == 0 {
-            hs.push(i); f[i] = 1;
-            let b1 = add(rels,quads,f,hs,&vec![i]);
-            restore(f,hs,k);
-            if b1 { continue; }
-            hs.push(i); f[i] = -1;
-            let b2 = add(rels,quads,f,hs,&vec![i]);
-            restore(f,hs,k);
-            if b2 { continue; }
-            return false;
-        }
+    // with weaker conditions (see tables::from_args) some may have survivors.
+    let start = |v: u64| { let mut run = Run::new(v, limits); run.stages = stages; run_signature(&t, run, dump) };
+    let sat: Vec<u64> = signatures(&t).par_iter().filter(|v| start(**v)).cloned().collect();
+    if !sat.is_empty() {
+        println!("satisfiable: {}", sat.iter().map(|v| v.to_string()).collect::<Vec<String>>().join(" "));
     }
-    return true;
-}
-
-// Performs the two-bit-check described in page 10.
-fn two_bit_check(rels: &Vec<Vec<Crel>>, quads: & Vec<Vec<Quad>>,
-                 f: &mut Vec<i8>, hs: &mut Vec<usize>) -> bool {
-    let k = hs.len();
-    for i in 0..N*(N-1)*(N-2)/6 {
-        't: for j in i+1..N*(N-1)*(N-2)/6 {
-            if f[i] == 0 && f[j] == 0 {
-                for (fi,fj) in [(1_i8,1_i8), (1,-1), (-1,1), (-1,-1)].iter() {
-                    hs.push(i); hs.push(j);
-                    f[i] = *fi; f[j] = *fj;
-                    let b = add(rels,quads,f,hs,&vec![i,j]);
-                    restore(f,hs,k);
-                    if b { continue 't; }
-                }
-                return false;
-            }
-        }
-    }
-    return true;
-}
-
-fn three_bit_check(rels: &Vec<Vec<Crel>>, quads: & Vec<Vec<Quad>>,
-                   f: &mut Vec<i8>, hs: &mut Vec<usize>) -> bool {
-    let k = hs.len();
-    for a in 0..N*(N-1)*(N-2)/6 {
-        for b in a+1..N*(N-1)*(N-2)/6 {
-            't: for c in b+1..N*(N-1)*(N-2)/6 {
-                if f[a] == 0 && f[b] == 0 && f[c] == 0 {
-                    for (fa,fb,fc) in [(1_i8,1_i8,1_i8),(1,1,-1),(1,-1,1),(1,-1,-1),
-                                       (-1,1,1),(-1,1,-1),(-1,-1,1),(-1,-1,-1)].iter() {
-                        hs.push(a); hs.push(b); hs.push(c);
-                        f[a] = *fa; f[b] = *fb; f[c] = *fc;
-                        let b = add(rels,quads,f,hs,&vec![a,b,c]);
-                        restore(f,hs,k);
-                        if b { continue 't; }
-                    }
-                    return false;
-                }
-            }
-        }
-    }
-    return true;
-}
-
-// It attempts to assign every triplet involved in some u_j without satisfying none
-// of the convex relations, and all of the geometric ones.
-// It performs other types of checks if it assigned all triplets involved in
-// u_j for all j in 1..=12.
-// This assumes triplets in u_1 are already assigned.
-fn search(rels: &Vec<Vec<Crel>>, comp: & CompatibleRels, quads: & Vec<Vec<Quad>>,
-          f: &mut Vec<i8>, hs: &mut Vec<usize>, count: &mut i64) -> () {
-    match (1..=N-5).find(|j| get_u(f,*j).iter().any(|x| *x == 0)) {
-        None => if one_bit_check(rels,quads,f,hs) && two_bit_check(rels,quads,f,hs) && three_bit_check(rels,quads,f,hs)
-                { *count = *count + 1 },
-        Some(j) => {
-            let com = comp.get(&get_u(f,j-1)).unwrap();
-            let u = get_u(f, j);
-            for v in com.iter() {
-                if (0..v.len()).all(|i| u[i] == 0 || u[i] == v[i]) {
-                    let k = hs.len();
-                    let b = set_u(rels,quads,f,hs,j,v);
-                    if b { search(rels,comp,quads,f,hs,count); }
-                    restore(f,hs,k);
-                }
-            }
-        }
-    }
-}
-
-fn try_signature(rels: &Vec<Vec<Crel>>, comp: & CompatibleRels, quads: & Vec<Vec<Quad>>, v: &Vec<i8>) {
-    let mut count: i64 = 0;
-    let mut f: Vec<i8> = vec![0;N*(N-1)*(N-2)/6];
-    let mut history: Vec<usize> = Vec::new();
-    let now = Instant::now();
-    let b = set_u(rels,quads,&mut f,&mut history,1,v);
-    if b { search(rels,comp,quads,&mut f,&mut history,&mut count); }
-    let elapsed_time = now.elapsed();
-    println!("idx: {}  n: {}  t: {}  v: {:?}",signature_idx(v),count,elapsed_time.as_secs() as f32/60.0,*v);
-}
-
-pub fn main() {
-    rayon::ThreadPoolBuilder::new().num_threads(1).build_global().unwrap();
-    let omega = concave6();
-    let comp = compatible(& omega);
-    let convexr = convex_rels6();
-    let quads = quadrilaterals();
-    let to_run: Vec<i32> = omega.iter().filter(|v| v[0] == 1).map(|v| signature_idx(v)).collect();
-    omega.par_iter().for_each(|v| if to_run.iter().any(|i| signature_idx(v) == *i)
-                              {try_signature(&convexr,&comp,&quads,v)});
 }
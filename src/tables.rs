@@ -0,0 +1,266 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+use crate::args::{Args, die};
+use crate::binfile::{checksum, invalid, read_u32, read_u64, write_u32, write_u64};
+use crate::comb::{cd, ntriples, tuples};
+use crate::cupcap::Chains;
+use crate::holes::Holes;
+use crate::omega::Omega;
+use crate::quints::Quints;
+use crate::relations::{Lazy, Materialized, Rel, Relations, all_relations, provider};
+
+pub type Quad = (usize,usize,usize,usize);
+
+const MAGIC: &[u8;8] = b"HETABLES";
+const VERSION: u32 = 1;
+
+// Everything the search needs for k-gons on n points. rels provides the convex
+// relations involving each triple, and quads[cd(a,b,c)] holds all quadrilateral
+// relations that involve a b and c. windows[j-1] lists the triples of u_j, in
+// the order of the positions of a signature. quints, when given, adds the
+// conditions on five points to the propagation, and fill tells whether the
+// quadrilaterals force their triples with fillquad or only refute. chains are
+// cups or caps forbidden on top of the convex relations, and holes empty convex
+// polygons.
+pub struct Tables {
+    pub k: usize,
+    pub n: usize,
+    pub omega: Omega,
+    pub rels: Box<dyn Relations>,
+    pub quads: Vec<Vec<Quad>>,
+    pub quints: Option<Arc<Quints>>,
+    pub fill: bool,
+    pub chains: Vec<Chains>,
+    pub holes: Option<Holes>,
+    windows: Vec<Vec<usize>>,
+}
+
+pub fn all_quads(n: usize) -> Vec<Quad> {
+    return tuples::<4>(n).map(|[a,b,c,d]| (cd(a,b,c),cd(a,b,d),cd(a,c,d),cd(b,c,d))).collect();
+}
+
+impl Tables {
+    pub fn new(k: usize, n: usize) -> Tables {
+        return Tables::from_omega(Omega::new(k, n-k+1), n);
+    }
+
+    pub fn from_omega(omega: Omega, n: usize) -> Tables {
+        let rels = provider(omega.k, n);
+        return Tables::index(omega, n, rels, all_quads(n));
+    }
+
+    // Tables with no quadrilateral conditions at all, neither in Omega nor in
+    // the propagation: only the convex relations constrain the triples.
+    pub fn combinatorial(k: usize, n: usize) -> Tables {
+        return Tables::from_omega(Omega::with_quads(k, n-k+1, false), n).without_quads();
+    }
+
+    // Tables which always generate the relations on demand.
+    pub fn lazy(omega: Omega, n: usize) -> Tables {
+        let k = omega.k;
+        return Tables::index(omega, n, Box::new(Lazy { k, n }), all_quads(n));
+    }
+
+    // Tables with only the given relations and quadrilaterals, and no windows to
+    // search (an empty Omega). Used to check what those constraints propagate.
+    pub fn restricted(k: usize, n: usize, rels: Vec<Rel>, quads: Vec<Quad>) -> Tables {
+        let omega = Omega::from_table(k, vec![], vec![], n-k+1);
+        return Tables::index(omega, n, Box::new(Materialized::new(n, rels)), quads);
+    }
+
+    fn index(omega: Omega, n: usize, rels: Box<dyn Relations>, all_quads: Vec<Quad>) -> Tables {
+        let k = omega.k;
+        let mut quads: Vec<Vec<Quad>> = vec![vec![]; ntriples(n)];
+        for (x,y,z,w) in all_quads {
+            for i in [x,y,z,w] { quads[i].push((x,y,z,w)); }
+        }
+        let windows = (1..=n-k+1).map(|j| {
+            let mut win = vec![0; ntriples(k)];
+            for [a,b,c] in tuples::<3>(k) {
+                win[cd(a,b,c)] = cd(a+j-1,b+j-1,c+j-1);
+            }
+            win
+        }).collect();
+        return Tables { k, n, omega, rels, quads, quints: None, fill: true, chains: vec![], holes: None, windows };
+    }
+
+    pub fn with_quints(mut self) -> Tables {
+        self.quints = Some(Arc::new(Quints::new(self.n)));
+        return self;
+    }
+
+    // The same tables without the quadrilaterals in the propagation (Omega is
+    // left as it is).
+    pub fn without_quads(mut self) -> Tables {
+        for q in self.quads.iter_mut() { q.clear(); }
+        return self;
+    }
+
+    pub fn without_fill(mut self) -> Tables {
+        self.fill = false;
+        return self;
+    }
+
+    // The same tables with the five-point conditions and the fillquad setting
+    // of t, so that apart from the relations and quadrilaterals they propagate
+    // as t does.
+    pub fn propagating_like(mut self, t: &Tables) -> Tables {
+        self.quints = t.quints.clone();
+        self.fill = t.fill;
+        return self;
+    }
+
+    // Tables with no convex relations, for windows of w points satisfying the
+    // quadrilateral conditions, to be used with chains.
+    pub fn windows_only(w: usize, n: usize) -> Tables {
+        let omega = Omega::constrained(w, n-w+1, true, vec![]);
+        return Tables::index(omega, n, Box::new(Materialized::new(n, vec![])), all_quads(n));
+    }
+
+    // Tables for n points without a k-hole: the holes replace the convex
+    // relations, which would forbid every convex k-gon.
+    pub fn holes(k: usize, n: usize) -> Tables {
+        let mut t = Tables::index(Omega::new(k, n-k+1), n, Box::new(Materialized::new(n, vec![])), all_quads(n));
+        t.holes = Some(Holes::new(k, n));
+        return t;
+    }
+
+    pub fn with_chains(mut self, c: Chains) -> Tables {
+        self.chains.push(c);
+        return self;
+    }
+
+    // the convex relations involving the triple t, using buf as scratch space.
+    pub fn relations<'a>(&'a self, t: usize, buf: &'a mut Vec<(i8,usize)>) -> std::slice::ChunksExact<'a, (i8,usize)> {
+        return self.rels.involving(t, buf).chunks_exact(self.k-2);
+    }
+
+    pub fn ntriples(&self) -> usize {
+        return ntriples(self.n);
+    }
+
+    // number of windows u_j.
+    pub fn nwindows(&self) -> usize {
+        return self.n - self.k + 1;
+    }
+
+    pub fn window(&self, j: usize) -> &[usize] {
+        return &self.windows[j-1];
+    }
+
+    // packed state of a window all whose triples are assigned.
+    pub fn full(&self) -> u64 {
+        return (1 << self.omega.b) - 1;
+    }
+
+    // Writes magic, version, k, n, the omega table, the convex relations (each
+    // one as its k-2 triples and a mask of its negative signs), the
+    // quadrilaterals, and a checksum of all of the above.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut w: Vec<u8> = Vec::new();
+        w.extend_from_slice(MAGIC);
+        write_u32(&mut w, VERSION)?;
+        write_u32(&mut w, self.k as u32)?;
+        write_u32(&mut w, self.n as u32)?;
+        self.omega.write_table(&mut w)?;
+        let rels = all_relations(self.k, self.n);
+        write_u32(&mut w, rels.len() as u32)?;
+        for rel in rels.iter() {
+            for (_,t) in rel.iter() { write_u32(&mut w, *t as u32)?; }
+            write_u32(&mut w, rel.iter().enumerate().fold(0, |m,(i,(s,_))| if *s < 0 { m | 1 << i } else { m }))?;
+        }
+        let quads = all_quads(self.n);
+        write_u32(&mut w, quads.len() as u32)?;
+        for (x,y,z,q) in quads.iter() {
+            for i in [x,y,z,q] { write_u32(&mut w, *i as u32)?; }
+        }
+        let sum = checksum(&w);
+        write_u64(&mut w, sum)?;
+        return fs::write(path, w);
+    }
+
+    // Loads tables written by save, failing unless they are intact and for
+    // k-gons on n points.
+    pub fn load(path: &Path, k: usize, n: usize) -> io::Result<Tables> {
+        let bytes = fs::read(path)?;
+        if bytes.len() < MAGIC.len() + 8 || &bytes[..MAGIC.len()] != MAGIC { return Err(invalid("not a tables file")); }
+        let (body, sum) = bytes.split_at(bytes.len() - 8);
+        if checksum(body) != read_u64(&mut &sum[..])? { return Err(invalid("tables checksum mismatch")); }
+        let mut r = &body[MAGIC.len()..];
+        if read_u32(&mut r)? != VERSION { return Err(invalid("unsupported tables version")); }
+        let (file_k, file_n) = (read_u32(&mut r)? as usize, read_u32(&mut r)? as usize);
+        if (file_k, file_n) != (k, n) {
+            return Err(invalid(&format!("tables are for k = {}, n = {}, not k = {}, n = {}", file_k, file_n, k, n)));
+        }
+        let (ok, sigs, comp) = Omega::read_table(&mut r)?;
+        if ok != k { return Err(invalid("omega table is for a different k")); }
+        let omega = Omega::from_table(k, sigs, comp, n-k+1);
+        let nt = ntriples(n);
+        let triple = |r: &mut &[u8]| -> io::Result<usize> {
+            let t = read_u32(r)? as usize;
+            if t < nt { Ok(t) } else { Err(invalid("triple out of range")) }
+        };
+        let mut rels: Vec<Rel> = Vec::new();
+        for _ in 0..read_u32(&mut r)? {
+            let ts = (0..k-2).map(|_| triple(&mut r)).collect::<io::Result<Vec<usize>>>()?;
+            let signs = read_u32(&mut r)?;
+            rels.push(ts.into_iter().enumerate().map(|(i,t)| (if (signs >> i) & 1 == 1 { -1 } else { 1 }, t)).collect());
+        }
+        let mut quads = Vec::new();
+        for _ in 0..read_u32(&mut r)? {
+            quads.push((triple(&mut r)?, triple(&mut r)?, triple(&mut r)?, triple(&mut r)?));
+        }
+        if !r.is_empty() { return Err(invalid("trailing data in tables file")); }
+        return Ok(Tables::index(omega, n, Box::new(Materialized::new(n, rels)), quads));
+    }
+}
+
+// Reads the tables for k-gons on n points from the file given by --tables, or
+// computes them if there is none (reading Omega from its cache when possible).
+// In that case --relations lazy generates the relations on demand even if they
+// would fit in memory. --axioms 5 adds the conditions on five points to the
+// ones on four, --quads off drops the quadrilaterals (from Omega too, which is
+// then cached in its own file) and --fillquad off keeps them but only to refute.
+pub fn from_args(args: &Args, k: usize, n: usize) -> Tables {
+    if k < 4 || n < k { die("need 4 <= k <= n"); }
+    let quads = switch(args, "quads");
+    let t = if let Some(p) = args.get("tables") {
+        if !quads { die("--quads off cannot use --tables"); }
+        Tables::load(Path::new(p), k, n).unwrap_or_else(|e| die(&format!("{}: {}", p, e)))
+    } else {
+        let path = if quads { format!("omega{}.bin", k) } else { format!("omega{}-noquads.bin", k) };
+        let omega = Omega::cached(k, n-k+1, quads, Path::new(&path)).unwrap_or_else(|e| die(&format!("{}: {}", path, e)));
+        match args.get("relations") {
+            None | Some("auto") => Tables::from_omega(omega, n),
+            Some("lazy") => Tables::lazy(omega, n),
+            Some(r) => die(&format!("unknown relations provider {}", r)),
+        }
+    };
+    let t = if quads { t } else { t.without_quads() };
+    let t = if switch(args, "fillquad") { t } else { t.without_fill() };
+    return match args.num("axioms", 4) {
+        4 => t,
+        5 => t.with_quints(),
+        a => die(&format!("--axioms must be 4 or 5, not {}", a)),
+    };
+}
+
+// The option key given as on or off, on by default.
+fn switch(args: &Args, key: &str) -> bool {
+    return match args.get(key) {
+        None | Some("on") => true,
+        Some("off") => false,
+        Some(v) => die(&format!("--{} must be on or off, not {}", key, v)),
+    };
+}
+
+// he precompute --k 6 --n 17 -o tables.bin
+pub fn main(args: &Args) {
+    let (k, n) = (args.num("k", 6), args.num("n", 17));
+    if k < 4 || n < k { die("need 4 <= k <= n"); }
+    let out = args.required("o");
+    Tables::new(k, n).save(Path::new(out)).unwrap_or_else(|e| die(&format!("{}: {}", out, e)));
+}
+
@@ -0,0 +1,15 @@
+#![allow(clippy::needless_return)]
+
+// The commands taking a signature of k points refuse fewer than k points, for
+// which the number of windows n-k+1 would wrap around, instead of running.
+
+use std::process::Command;
+
+#[test]
+fn fewer_points_than_k() {
+    for cmd in ["sig", "estimate", "explain"] {
+        let out = Command::new(env!("CARGO_BIN_EXE_he")).args([cmd, "983040", "--k", "6", "--n", "4"]).output().unwrap();
+        assert!(!out.status.success(), "{}", cmd);
+        assert_eq!(String::from_utf8_lossy(&out.stderr), "he: need 4 <= k <= n\n", "{}", cmd);
+    }
+}
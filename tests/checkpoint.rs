@@ -0,0 +1,37 @@
+#![allow(clippy::needless_return)]
+
+// A run stopped at any node budget and resumed from its checkpoint until it
+// finishes counts the same nodes, leaves and survivors as a run without limits.
+
+use he::solver::{Limits, Run, try_signature};
+use he::szekeres6::signatures;
+use he::tables::Tables;
+
+fn counts(run: &Run) -> (u64, u64, [u64;3]) {
+    return (run.nodes, run.leaves, run.passed);
+}
+
+#[test]
+fn resumed_runs() {
+    let t = Tables::new(5, 8);
+    for v in signatures(&t).into_iter().take(6) {
+        let mut full = Run::new(v, Limits::default());
+        try_signature(&t, &mut full);
+        for budget in 1..=6 {
+            let limits = Limits { max_nodes: Some(budget), ..Limits::default() };
+            let mut run = Run::new(v, limits);
+            let mut slices = 0;
+            loop {
+                try_signature(&t, &mut run);
+                if !run.stopped { break; }
+                slices += 1;
+                assert!(slices <= full.nodes, "signature {} budget {}: no progress", v, budget);
+                run = Run::resume(&run.checkpoint(), limits).unwrap();
+            }
+            assert_eq!(counts(&run), counts(&full), "signature {} budget {}", v, budget);
+        }
+    }
+    // a checkpoint without the number of stages, as they were first written.
+    let run = Run::resume("512 3 1 1 0 0 0.500 0.2", Limits::default()).unwrap();
+    assert_eq!((run.stages, run.passed[0], run.checkpoint().split_whitespace().nth(6)), (3, 1, Some("3")));
+}
@@ -0,0 +1,35 @@
+// The reference enumeration shared by the tests: every complete assignment of
+// the triples of n points satisfying none of the relations rels and having
+// every quadruple of quads in VALID, found by assigning the triples in the
+// order of cd and checking each constraint as soon as its last triple is set.
+// The first triples take the values of prefix.
+
+use he::comb::ntriples;
+use he::quads::VALID;
+use he::relations::Rel;
+use he::tables::Quad;
+
+pub fn completions(n: usize, rels: &[Rel], quads: &[Quad], prefix: &[i8], visit: &mut impl FnMut(&[i8])) {
+    let nt = ntriples(n);
+    let mut rels_at: Vec<Vec<&Rel>> = vec![vec![]; nt];
+    for rel in rels.iter() { rels_at[rel.iter().map(|(_,t)| *t).max().unwrap()].push(rel); }
+    let mut quads_at: Vec<Vec<Quad>> = vec![vec![]; nt];
+    for q in quads.iter() { quads_at[q.3].push(*q); }
+    fn extend(rels: &[Vec<&Rel>], quads: &[Vec<Quad>], prefix: &[i8], f: &mut Vec<i8>, t: usize, visit: &mut impl FnMut(&[i8])) {
+        if t == f.len() { visit(f); return; }
+        let choices: &[i8] = if t < prefix.len() { &prefix[t..t+1] } else { &[1,-1] };
+        'x: for x in choices {
+            f[t] = *x;
+            for (p,q,r,s) in quads[t].iter() {
+                if !VALID.contains(&(f[*p],f[*q],f[*r],f[*s])) { continue 'x; }
+            }
+            for rel in rels[t].iter() {
+                let (s0,t0) = rel[0];
+                if rel.iter().all(|(s,u)| s*f[*u] == s0*f[t0]) { continue 'x; }
+            }
+            extend(rels, quads, prefix, f, t+1, visit);
+        }
+        f[t] = 0;
+    }
+    extend(&rels_at, &quads_at, prefix, &mut vec![0; nt], 0, visit);
+}
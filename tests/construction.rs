@@ -0,0 +1,31 @@
+// The construction of Erdős and Szekeres: 2^(k-2) points in general position
+// whose largest convex polygon has k-1 points, so that f(k) > 2^(k-2).
+
+use he::comb::binom;
+use he::construction::{cups_caps, kgon_free, largest_convex, points, signature};
+
+#[test]
+fn construction() {
+    for k in 4..=8 {
+        let pts = points(k);
+        assert_eq!(pts.len(), 1 << (k-2));
+        assert!(pts.windows(2).all(|w| w[0].0 < w[1].0));
+        let f = signature(&pts);
+        assert!(!f.contains(&0), "k = {}", k);
+        assert_eq!(largest_convex(&pts), k-1, "k = {}", k);
+        if k <= 7 { assert!(kgon_free(k, &f), "k = {}", k); }
+        for i in 0..=k-2 { assert_eq!(cups_caps(k-i, i+2).len(), binom(k-2, i)); }
+    }
+}
+
+// Points on a parabola are in convex position, and a point inside them does not
+// make a larger convex polygon.
+#[test]
+fn largest_convex_polygon() {
+    let parabola: Vec<(i64,i64)> = (0..7).map(|x| (2*x, 4*x*x)).collect();
+    assert_eq!(largest_convex(&parabola), 7);
+    assert!(!kgon_free(7, &signature(&parabola)));
+    let mut pts = parabola.clone();
+    pts.insert(3, (5, 30));
+    assert_eq!(largest_convex(&pts), 7);
+}
@@ -0,0 +1,46 @@
+#![allow(clippy::needless_return)]
+
+// Splitting the search of a pentagon signature on 8 points in cubes, solving
+// them and merging their results gives the counts of a plain run.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use he::solver::{Limits, Run, try_signature};
+use he::szekeres6::signatures;
+use he::tables::Tables;
+
+fn he(dir: &Path, args: &[&str]) -> String {
+    let out = Command::new(env!("CARGO_BIN_EXE_he")).args(["cubes"]).args(args).args(["--k", "5", "--n", "8", "--progress", "0"])
+        .current_dir(dir).output().unwrap();
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    return String::from_utf8(out.stdout).unwrap();
+}
+
+#[test]
+fn split_solve_merge() {
+    let dir = std::env::temp_dir().join(format!("he-cubes-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let t = Tables::new(5, 8);
+    let run = signatures(&t).into_iter().map(|v| {
+        let mut run = Run::new(v, Limits::default());
+        try_signature(&t, &mut run);
+        run
+    }).max_by_key(|r| r.leaves).unwrap();
+    assert!(run.passed[0] > 0);
+    let v = run.v.to_string();
+    he(&dir, &["split", &v, "--depth", "2", "-o", "cubes.txt"]);
+    let cubes = fs::read_to_string(dir.join("cubes.txt")).unwrap();
+    assert!(cubes.lines().count() > 1, "{}", cubes);
+    let results = he(&dir, &["solve", "cubes.txt"]);
+    fs::write(dir.join("results.txt"), &results).unwrap();
+    let mut total = Run::new(run.v, Limits::default());
+    for l in results.lines() {
+        total.merge(&Run::resume(l.split_once(' ').unwrap().1, Limits::default()).unwrap());
+    }
+    assert_eq!((total.leaves, total.passed), (run.leaves, run.passed));
+    let merged = he(&dir, &["merge", "cubes.txt", "results.txt"]);
+    assert!(merged.starts_with(&format!("idx: {}  n: {}  ", v, run.survivors())), "{}", merged);
+    fs::remove_dir_all(&dir).unwrap();
+}
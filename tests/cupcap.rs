@@ -0,0 +1,55 @@
+#![allow(clippy::needless_return)]
+// The cup-cap numbers f(a,b) = C(a+b-4,a-2)+1 of Erdős and Szekeres, by the
+// search with cups and caps forbidden, and with a convex k-gon forbidden too
+// against a count of the complete assignments.
+
+mod common;
+
+use he::comb::cd;
+use he::cupcap::{formula, number};
+use he::relations::all_relations;
+use he::tables::all_quads;
+
+#[test]
+fn cup_cap_numbers() {
+    for (a,b) in [(3,3),(3,4),(4,3),(3,5),(4,4),(4,5),(5,4)] {
+        assert_eq!(number(a, b, None, |_,_| {}), formula(a, b), "f({},{})", a, b);
+    }
+}
+
+// Whether f has a chain of len points all whose consecutive triples are sign:
+// longest[i][j] is the longest one ending by the points i < j.
+fn chain(f: &[i8], n: usize, len: usize, sign: i8) -> bool {
+    let mut longest = vec![vec![2; n+1]; n+1];
+    for j in 1..=n {
+        for i in 1..j {
+            longest[i][j] = (1..i).filter(|h| f[cd(*h,i,j)] == sign).map(|h| longest[h][i] + 1).max().unwrap_or(2);
+            if longest[i][j] >= len { return true; }
+        }
+    }
+    return false;
+}
+
+// The least n such that every complete assignment of n points without a convex
+// k-gon has an a-cup or a b-cap.
+fn brute(a: usize, b: usize, k: usize) -> usize {
+    let mut n = a.max(b).max(k);
+    loop {
+        let mut free = false;
+        common::completions(n, &all_relations(k, n), &all_quads(n), &[], &mut |f| {
+            free |= !chain(f, n, a, 1) && !chain(f, n, b, -1);
+        });
+        if !free { return n; }
+        n += 1;
+    }
+}
+
+// With a 4-cup and a 5-cap forbidden, 8 points are already enough for
+// pentagons, fewer than f(4,5) = 11 or f(5) = 9.
+#[test]
+fn mixed() {
+    for (a,b,k) in [(4,4,5),(3,5,5),(4,5,5),(5,4,5),(5,5,5),(4,6,5),(4,4,6)] {
+        assert_eq!(number(a, b, Some(k), |_,_| {}), brute(a, b, k), "a = {}, b = {}, k = {}", a, b, k);
+    }
+    assert_eq!(brute(4, 5, 5), 8);
+}
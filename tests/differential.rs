@@ -0,0 +1,101 @@
+#![allow(clippy::needless_return)]
+// Cross-check of the three searches against a reference which assigns every
+// triple in order and rejects an assignment as soon as the last triple of a
+// convex relation (from relations::all_relations) or of a quadruple of points
+// is set. Each search counts something else:
+//   brute_search  every assignment without a convex 5-gon;
+//   szekeres5     the assignments of the windows, propagating the relations;
+//   szekeres6     the same with the quadruples, after the bit checks.
+// The first is the number of complete assignments of the reference without
+// quadruples, the others the number of distinct restrictions of them to the
+// windows (the triples a < b < c with c - a < k): on these instances every
+// leaf left by the searches extends to a complete assignment.
+
+mod common;
+
+use std::collections::BTreeSet;
+use he::comb::uncd;
+use he::relations::all_relations;
+use he::tables::{Tables, all_quads};
+use he::{brute_search, extremal, szekeres5, szekeres6};
+
+// The number of complete assignments of n points whose first triples are
+// prefix and without a convex k-gon, and the number of their distinct
+// restrictions to the windows.
+fn reference(k: usize, n: usize, quads: bool, prefix: &[i8]) -> (u64, u64) {
+    let quads = if quads { all_quads(n) } else { vec![] };
+    let (mut complete, mut windows) = (0, BTreeSet::new());
+    common::completions(n, &all_relations(k, n), &quads, prefix, &mut |f| {
+        complete += 1;
+        let mut w = 0u64;
+        for (i,x) in f.iter().enumerate() {
+            let (a,_,c) = uncd(i);
+            if c - a < k { w = w << 1 | (*x == 1) as u64; }
+        }
+        windows.insert(w);
+    });
+    return (complete, windows.len() as u64);
+}
+
+#[test]
+fn generic_solver() {
+    for k in [4,5] {
+        for n in k..=8 {
+            let (_, windows) = reference(k, n, true, &[1]);
+            assert_eq!(szekeres6::total(&Tables::new(k, n)), windows, "k = {}, n = {}", k, n);
+        }
+    }
+}
+
+// Without quadruples brute_search and the reference enumerate every assignment
+// without a convex pentagon, which is only fast enough up to 7 points.
+fn without_quads(to: usize) {
+    for n in 5..=to {
+        let (complete, windows) = reference(5, n, false, &[1]);
+        assert_eq!(brute_search::count(n) as u64, complete, "n = {}", n);
+        assert_eq!(szekeres5::count(n) as u64, windows, "n = {}", n);
+    }
+}
+
+#[test]
+fn pentagons_without_quads() {
+    without_quads(7);
+}
+
+// The same on 8 points for a few of the signatures of u_1 (the triples of
+// points 1..5, the first 10 in the order of cd), a few seconds each.
+#[test]
+fn pentagons_without_quads_on_8_points_some_signatures() {
+    let minus = |ts: &[usize]| -> Vec<i8> { (0..10).map(|i| if ts.contains(&i) { -1 } else { 1 }).collect() };
+    for v in [minus(&[1,3,4,5,6,7,8,9]), minus(&[3,4,5,6,7,8,9]), minus(&[1,2,4,5,6,7,8,9]), minus(&[2,4,5,6,7,8,9])] {
+        let (complete, windows) = reference(5, 8, false, &v);
+        assert!(windows > 0, "{:?}", v);
+        assert_eq!(brute_search::count_with(8, &v) as u64, complete, "{:?}", v);
+        assert_eq!(szekeres5::count_with(8, &v) as u64, windows, "{:?}", v);
+    }
+}
+
+// About 17 minutes in release mode on one core, so only run on demand with
+//   cargo test --release --test differential -- --ignored
+#[test]
+#[ignore = "about 17 minutes, run with --ignored"]
+fn pentagons_without_quads_on_8_points() {
+    without_quads(8);
+}
+
+// extremal completes the leaves into every assignment, and finds twice the
+// complete assignments of the reference (which fixes f(1,2,3) = 1), with or
+// without quadruples.
+#[test]
+fn extremal_assignments() {
+    for (k,n) in [(4,4),(4,5),(5,5),(5,6),(5,7),(5,8)] {
+        let (complete, _) = reference(k, n, true, &[1]);
+        let (total, classes) = extremal::enumerate(&Tables::new(k, n));
+        assert_eq!(total, 2 * complete, "k = {}, n = {}", k, n);
+        assert!(classes.len() as u64 <= total && total <= 4 * classes.len() as u64);
+    }
+    for n in 5..=7 {
+        let (complete, _) = reference(5, n, false, &[1]);
+        assert_eq!(extremal::enumerate(&Tables::combinatorial(5, n)).0, 2 * complete, "n = {}", n);
+    }
+}
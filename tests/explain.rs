@@ -0,0 +1,34 @@
+#![allow(clippy::needless_return)]
+
+// The cores of explain refute their conflict under the conditions of the tables
+// they come from, and stop doing so once any relation or quadrilateral is
+// dropped.
+
+use he::explain::{Core, conflicts, core, refutes};
+use he::szekeres6::signatures;
+use he::tables::Tables;
+
+fn check(t: &Tables) {
+    let mut cores = 0;
+    for v in signatures(t).into_iter().take(4) {
+        for c in conflicts(t, v, None, 2) {
+            let c = core(t, &c);
+            assert!(refutes(t, &c), "{}", v);
+            for i in 0..c.rels.len() + c.quads.len() {
+                let (mut rels, mut quads) = (c.rels.clone(), c.quads.clone());
+                if i < rels.len() { rels.remove(i); } else { quads.remove(i - rels.len()); }
+                let smaller = Core { assumed: c.assumed.clone(), probe: c.probe.clone(), rels, quads };
+                assert!(!refutes(t, &smaller), "{}: constraint {} can be dropped", v, i);
+            }
+            cores += 1;
+        }
+    }
+    assert!(cores > 0);
+}
+
+#[test]
+fn cores() {
+    check(&Tables::new(5, 9));
+    check(&Tables::new(5, 9).without_fill());
+    check(&Tables::new(5, 9).with_quints());
+}
@@ -0,0 +1,48 @@
+#![allow(clippy::needless_return)]
+// Empty convex polygons: h(4) = 5 and h(5) = 10, and the CNF export against the
+// assignments found by the search.
+
+use he::comb::ntriples;
+use he::extremal;
+use he::holes::{number, write_cnf};
+use he::tables::Tables;
+
+#[test]
+fn hole_numbers() {
+    assert_eq!(number(4, |_,_| {}), 5);
+    assert_eq!(number(5, |_,_| {}), 10);
+}
+
+// The assignments of the triples satisfying the CNF, where an auxiliary
+// variable is true when both its literals are.
+fn models(k: usize, n: usize) -> u64 {
+    let mut out = Vec::new();
+    write_cnf(k, n, &mut out).unwrap();
+    let text = String::from_utf8(out).unwrap();
+    let nt = ntriples(n);
+    let clauses: Vec<Vec<i64>> = text.lines().filter(|l| !l.starts_with('c') && !l.starts_with('p'))
+        .map(|l| l.split_whitespace().map(|w| w.parse().unwrap()).filter(|l| *l != 0).collect()).collect();
+    let aux: Vec<&Vec<i64>> = clauses.iter().filter(|c| c.len() == 2 && c[0] < 0 && -c[0] as usize > nt).collect();
+    let nvars: usize = text.lines().find(|l| l.starts_with('p')).unwrap().split_whitespace().nth(2).unwrap().parse().unwrap();
+    let mut count = 0;
+    for m in 0..1_u64 << nt {
+        let mut v = vec![true; nvars+1];
+        for t in 0..nt { v[t+1] = (m >> t) & 1 == 1; }
+        let holds = |v: &Vec<bool>, l: i64| if l > 0 { v[l as usize] } else { !v[-l as usize] };
+        for c in aux.iter() {
+            let z = -c[0] as usize;
+            let l = holds(&v, c[1]);
+            v[z] = v[z] && l;
+        }
+        if clauses.iter().all(|c| c.iter().any(|l| holds(&v, *l))) { count += 1; }
+    }
+    return count;
+}
+
+#[test]
+fn cnf_export() {
+    for (k,n) in [(4,4),(4,5),(5,5),(5,6)] {
+        let (total, _) = extremal::enumerate(&Tables::holes(k, n));
+        assert_eq!(2 * models(k, n), total, "k = {}, n = {}", k, n);
+    }
+}
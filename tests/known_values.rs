@@ -0,0 +1,42 @@
+// f(4) = 5 and f(5) = 9: there are assignments of f(k)-1 points without a
+// convex k-gon, and none of f(k) points.
+
+use he::{brute_search, szekeres5, szekeres6, tables::Tables};
+
+#[test]
+fn quadrilaterals() {
+    assert!(szekeres6::total(&Tables::new(4, 4)) > 0);
+    assert_eq!(szekeres6::total(&Tables::new(4, 5)), 0);
+}
+
+#[test]
+fn pentagons() {
+    assert!(szekeres6::total(&Tables::new(5, 8)) > 0);
+    assert_eq!(szekeres6::total(&Tables::new(5, 9)), 0);
+}
+
+#[test]
+fn pentagons_szekeres5() {
+    assert!(szekeres5::count(8) > 0);
+    assert_eq!(szekeres5::count(9), 0);
+}
+
+// brute_search assigns every triple, so it is only viable on a few points. On
+// 5 points every triple is in the single window, and it agrees with szekeres5.
+#[test]
+fn pentagons_brute_search() {
+    assert_eq!(brute_search::count(5), szekeres5::count(5));
+    assert!(brute_search::count(6) > 0);
+}
+
+// Without fillquad, or without the quadrilaterals at all, there are more
+// assignments of 8 points but still none of 9: the convex relations alone
+// force a pentagon.
+#[test]
+fn pentagons_without_geometry() {
+    assert_eq!(szekeres6::total(&Tables::new(5, 9).without_fill()), 0);
+    assert_eq!(szekeres6::total(&Tables::new(5, 9).without_quads()), 0);
+    assert_eq!(szekeres6::total(&Tables::combinatorial(5, 9)), 0);
+    assert!(szekeres6::total(&Tables::combinatorial(5, 8)) > szekeres6::total(&Tables::new(5, 8)));
+    assert!(!szekeres6::satisfiable(&Tables::combinatorial(5, 8)).is_empty());
+}
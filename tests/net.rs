@@ -0,0 +1,69 @@
+#![allow(clippy::needless_return)]
+
+// A coordinator of pentagons on 8 points on localhost with two workers, and a
+// third one which takes a unit and dies before sending its result: the unit is
+// issued again, malformed results are answered by ERR, and the total is the one
+// of a single process. A worker whose coordinator goes away before saying DONE
+// fails.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::process::{Child, Command, Stdio};
+use he::szekeres6;
+use he::tables::Tables;
+
+fn he(args: &[&str]) -> Command {
+    let mut c = Command::new(env!("CARGO_BIN_EXE_he"));
+    c.args(args).args(["--k", "5", "--n", "8", "--progress", "0"]).current_dir(env!("CARGO_TARGET_TMPDIR"));
+    return c;
+}
+
+fn ask(w: &mut TcpStream, r: &mut impl BufRead, req: &str) -> String {
+    w.write_all(format!("{}\n", req).as_bytes()).unwrap();
+    let mut line = String::new();
+    r.read_line(&mut line).unwrap();
+    return line.trim().to_string();
+}
+
+#[test]
+fn coordinator_and_workers() {
+    let mut coordinator = he(&["coordinator", "--listen", "127.0.0.1:0", "--depth", "1"])
+        .stdout(Stdio::piped()).stderr(Stdio::piped()).spawn().unwrap();
+    // stderr is kept open until the coordinator exits.
+    let mut stderr = BufReader::new(coordinator.stderr.take().unwrap()).lines();
+    let addr = stderr.by_ref().map(|l| l.unwrap()).find_map(|l| l.strip_prefix("listening on ").map(|a| a.to_string())).unwrap();
+    let mut dying = TcpStream::connect(&addr).unwrap();
+    let mut r = BufReader::new(dying.try_clone().unwrap());
+    assert!(ask(&mut dying, &mut r, "GET").starts_with("UNIT "));
+    assert_eq!(ask(&mut dying, &mut r, "RESULT 0 garbage"), "ERR");
+    assert_eq!(ask(&mut dying, &mut r, "RESULT x 512 0 0 0 0 0 0 -"), "ERR");
+    assert_eq!(ask(&mut dying, &mut r, "HELLO"), "ERR");
+    let mut workers: Vec<Child> = (0..2).map(|_| he(&["worker", "--connect", &addr]).spawn().unwrap()).collect();
+    drop(r);
+    drop(dying);
+    let out = coordinator.wait_with_output().unwrap();
+    assert!(out.status.success());
+    for w in workers.iter_mut() { assert!(w.wait().unwrap().success()); }
+    drop(stderr);
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    let total = stdout.lines().find_map(|l| l.strip_prefix("total: ")).unwrap();
+    assert_eq!(total.parse::<u64>().unwrap(), szekeres6::total(&Tables::new(5, 8)));
+}
+
+#[test]
+fn coordinator_killed() {
+    let mut coordinator = he(&["coordinator", "--listen", "127.0.0.1:0"]).stderr(Stdio::piped()).spawn().unwrap();
+    let mut stderr = BufReader::new(coordinator.stderr.take().unwrap()).lines();
+    let addr = stderr.by_ref().map(|l| l.unwrap()).find_map(|l| l.strip_prefix("listening on ").map(|a| a.to_string())).unwrap();
+    // every unit is taken here, so that the worker is told to wait.
+    let mut taker = TcpStream::connect(&addr).unwrap();
+    let mut r = BufReader::new(taker.try_clone().unwrap());
+    while ask(&mut taker, &mut r, "GET").starts_with("UNIT ") {}
+    let worker = he(&["worker", "--connect", &addr]).stderr(Stdio::piped()).spawn().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    coordinator.kill().unwrap();
+    coordinator.wait().unwrap();
+    let out = worker.wait_with_output().unwrap();
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).starts_with(&format!("he: {}: ", addr)), "{}", String::from_utf8_lossy(&out.stderr));
+}
@@ -0,0 +1,97 @@
+#![allow(clippy::needless_return)]
+// Soundness of solver::add: on random partial assignments of a few points, a
+// contradiction reported by add must leave no completion, and every value it
+// forces must be the one of every completion. The completions are all the
+// assignments of every triple satisfying no convex relation and having every
+// quadruple of points in VALID, listed once by exhaustive enumeration.
+
+mod common;
+
+use he::estimate::Rng;
+use he::relations::{Rel, all_relations};
+use he::solver::add;
+use he::tables::{Quad, Tables, all_quads};
+
+// Every complete assignment satisfying the constraints, as the mask of the
+// triples assigned to 1.
+fn completions(n: usize, rels: &[Rel], quads: &[Quad]) -> Vec<u64> {
+    let mut out = Vec::new();
+    common::completions(n, rels, quads, &[], &mut |f| {
+        out.push(f.iter().enumerate().filter(|(_,x)| **x == 1).fold(0, |m,(i,_)| m | 1 << i));
+    });
+    return out;
+}
+
+fn agrees(c: u64, f: &[i8]) -> bool {
+    return f.iter().enumerate().all(|(i,x)| *x == 0 || (*x == 1) == ((c >> i) & 1 == 1));
+}
+
+// Runs add on trials partial assignments, half of them restrictions of some
+// completion and half of them random, and returns how many values it forced
+// and how many contradictions it reported.
+fn fuzz(t: &Tables, all: &[u64], trials: usize, seed: u64) -> (usize, usize) {
+    let nt = t.ntriples();
+    let mut rng = Rng::new(seed);
+    let (mut forced, mut refuted) = (0, 0);
+    for trial in 0..trials {
+        let density = 1 + rng.below(7);
+        let from = if trial % 2 == 0 { Some(all[rng.below(all.len())]) } else { None };
+        let mut f = vec![0; nt];
+        for (i,x) in f.iter_mut().enumerate() {
+            if rng.below(10) >= density { continue; }
+            let one = match from { Some(c) => (c >> i) & 1 == 1, None => rng.below(2) == 0 };
+            *x = if one { 1 } else { -1 };
+        }
+        let partial = f.clone();
+        let added: Vec<usize> = (0..nt).filter(|i| f[*i] != 0).collect();
+        let mut hs = added.clone();
+        let ok = add(t, &mut f, &mut hs, &added);
+        let extending: Vec<u64> = all.iter().copied().filter(|c| agrees(*c, &partial)).collect();
+        if !ok {
+            assert!(extending.is_empty(), "false contradiction from {:?}", partial);
+            refuted += 1;
+            continue;
+        }
+        assert!(from.is_none() || !extending.is_empty());
+        for c in extending.iter() {
+            assert!(agrees(*c, &f), "unsound forcing from {:?} to {:?}", partial, f);
+        }
+        assert_eq!(hs.len(), f.iter().filter(|x| **x != 0).count());
+        forced += hs.len() - added.len();
+    }
+    return (forced, refuted);
+}
+
+#[test]
+fn hexagon_relations() {
+    let rels = all_relations(6, 6);
+    let t = Tables::restricted(6, 6, rels.clone(), vec![]);
+    let (forced, refuted) = fuzz(&t, &completions(6, &rels, &[]), 2000, 1);
+    assert!(forced > 0 && refuted > 0);
+}
+
+// Without relations, every value forced comes from fillquad.
+#[test]
+fn fillquad() {
+    let t = Tables::restricted(6, 6, vec![], all_quads(6));
+    let (forced, refuted) = fuzz(&t, &completions(6, &[], &all_quads(6)), 2000, 2);
+    assert!(forced > 0 && refuted > 0);
+}
+
+#[test]
+fn hexagon_relations_and_quads() {
+    for n in [7, 8] {
+        let t = Tables::new(6, n);
+        let (forced, refuted) = fuzz(&t, &completions(n, &all_relations(6, n), &all_quads(n)), 2000, n as u64);
+        assert!(forced > 0 && refuted > 0);
+    }
+}
+
+// The conditions on five points do not change the completions (see
+// tests/quints.rs), only what add forces.
+#[test]
+fn pentagons() {
+    let t = Tables::new(6, 7).with_quints();
+    let (forced, refuted) = fuzz(&t, &completions(7, &all_relations(6, 7), &all_quads(7)), 2000, 5);
+    assert!(forced > 0 && refuted > 0);
+}
@@ -0,0 +1,41 @@
+// The hand written VALID and fillquad agree with the ones derived from the
+// orientations of four points.
+
+use he::quads::{VALID, fillquad, forced, realizable, valid};
+use he::signature::{Signature, quadrilaterals};
+
+#[test]
+fn realizable_signatures() {
+    // (2.3): the orientations change sign at most once along abc, abd, acd, bcd.
+    let real = realizable();
+    assert_eq!(real.len(), 8);
+    for q in real {
+        let s = [q.0,q.1,q.2,q.3];
+        assert!(s.windows(2).filter(|w| w[0] != w[1]).count() <= 1, "{:?}", q);
+    }
+}
+
+#[test]
+fn valid_table() {
+    let mut table = VALID.to_vec();
+    table.sort();
+    table.dedup();
+    assert_eq!(table.len(), VALID.len());
+    assert_eq!(table, valid());
+}
+
+#[test]
+fn fillquad_table() {
+    let signs = [-1,0,1];
+    for a in signs { for b in signs { for c in signs { for d in signs {
+        assert_eq!(fillquad((a,b,c,d)), forced((a,b,c,d)), "{:?}", (a,b,c,d));
+    }}}}
+}
+
+#[test]
+fn quadrilateral_labels() {
+    // he sig 5: the signs of 2356 change twice along abc, abd, acd, bcd.
+    let lines = quadrilaterals(&Signature::from_index(6, 5));
+    assert!(lines.contains(&"(2,3,5,6) --+- invalid".to_string()), "{:?}", lines);
+    assert!(lines.contains(&"(1,2,3,4) ---- convex".to_string()), "{:?}", lines);
+}
@@ -0,0 +1,100 @@
+#![allow(clippy::needless_return)]
+
+// A queue of pentagons on 8 points where one job was claimed by a worker since
+// killed, one by a worker of another host which stopped writing its checkpoint
+// and one by a worker of another host still running: a worker takes back the
+// first two, runs every job it can claim to the end with the counts of a plain
+// run, and leaves the last claim alone.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, SystemTime};
+use he::queue::hostname;
+use he::solver::{Limits, Run, try_signature};
+use he::szekeres6::signatures;
+use he::tables::Tables;
+
+fn he(dir: &Path, args: &[&str]) {
+    let out = Command::new(env!("CARGO_BIN_EXE_he")).args(args).args(["--k", "5", "--n", "8", "--progress", "0"])
+        .current_dir(dir).output().unwrap();
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+}
+
+// Moves the job of v from todo to running as claimed by pid on host, written
+// age ago.
+fn claim(dir: &Path, v: u64, host: &str, pid: u32, age: Duration) {
+    let path = dir.join("jobs/running").join(format!("{}.{}.{}", v, host, pid));
+    fs::rename(dir.join("jobs/todo").join(v.to_string()), &path).unwrap();
+    fs::File::options().write(true).open(&path).unwrap().set_modified(SystemTime::now() - age).unwrap();
+}
+
+#[test]
+fn reclaimed_jobs() {
+    let dir = std::env::temp_dir().join(format!("he-queue-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    he(&dir, &["enqueue", "--dir", "jobs"]);
+    let t = Tables::new(5, 8);
+    let sigs = signatures(&t);
+    assert!(sigs.len() > 3);
+    let mut killed = Command::new("sleep").arg("60").spawn().unwrap();
+    killed.kill().unwrap();
+    killed.wait().unwrap();
+    claim(&dir, sigs[0], &hostname(), killed.id(), Duration::ZERO);
+    claim(&dir, sigs[1], "elsewhere", 1, Duration::from_secs(3600));
+    claim(&dir, sigs[2], "elsewhere", 2, Duration::ZERO);
+    he(&dir, &["worker", "--dir", "jobs", "--stale", "600"]);
+    let running: Vec<String> = fs::read_dir(dir.join("jobs/running")).unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string()).collect();
+    assert_eq!(running, vec![format!("{}.elsewhere.2", sigs[2])]);
+    for v in sigs.iter().filter(|v| **v != sigs[2]) {
+        let line = fs::read_to_string(dir.join("jobs/done").join(v.to_string())).unwrap();
+        let count: u64 = line.split_whitespace().nth(3).unwrap().parse().unwrap();
+        let mut run = Run::new(*v, Limits::default());
+        try_signature(&t, &mut run);
+        assert_eq!(count, run.survivors(), "{}", line);
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+// A job reclaimed by another worker while it runs: its worker gives it up
+// without failing and without a result, and the worker that took it over runs
+// it to the end. The claim of the other worker is put in place as soon as the
+// first one makes its own, in the middle of its first slices.
+#[test]
+fn reclaimed_while_running() {
+    let dir = std::env::temp_dir().join(format!("he-queue-running-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    he(&dir, &["enqueue", "--dir", "jobs"]);
+    let t = Tables::new(5, 8);
+    let runs: Vec<Run> = signatures(&t).into_iter().map(|v| {
+        let mut run = Run::new(v, Limits::default());
+        try_signature(&t, &mut run);
+        run
+    }).collect();
+    let longest = runs.iter().max_by_key(|r| r.nodes).unwrap();
+    for r in runs.iter().filter(|r| r.v != longest.v) {
+        fs::remove_file(dir.join("jobs/todo").join(r.v.to_string())).unwrap();
+    }
+    let running = dir.join("jobs/running");
+    let mut first = Command::new(env!("CARGO_BIN_EXE_he"))
+        .args(["worker", "--dir", "jobs", "--slice", "0.001", "--stale", "600", "--k", "5", "--n", "8", "--progress", "0"])
+        .current_dir(&dir).spawn().unwrap();
+    let claim = loop {
+        if let Some(e) = fs::read_dir(&running).unwrap().next() { break e.unwrap().path(); }
+    };
+    let other = running.join(format!("{}.elsewhere.1", longest.v));
+    fs::rename(&claim, &other).unwrap();
+    assert!(first.wait().unwrap().success());
+    let names: Vec<String> = fs::read_dir(&running).unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string()).collect();
+    assert_eq!(names, vec![format!("{}.elsewhere.1", longest.v)]);
+    he(&dir, &["worker", "--dir", "jobs", "--stale", "0"]);
+    assert_eq!(fs::read_dir(&running).unwrap().count(), 0);
+    let line = fs::read_to_string(dir.join("jobs/done").join(longest.v.to_string())).unwrap();
+    let count: u64 = line.split_whitespace().nth(3).unwrap().parse().unwrap();
+    assert_eq!(count, longest.survivors(), "{}", line);
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,26 @@
+// The conditions on five points.
+
+use he::quints::{Fill, NTRIPLES, fill_table, index, quad_consistent, realizable};
+
+// Every pentagon signature allowed by its quadrilaterals is realizable: there
+// are 62 of them, the signotopes of rank 3 on 5 elements. The conditions on five
+// points only add propagation, by looking at the pentagon as a whole.
+#[test]
+fn pentagons() {
+    let real = realizable();
+    assert_eq!(real.len(), 62);
+    assert_eq!(real, quad_consistent());
+}
+
+#[test]
+fn fill() {
+    let real = realizable();
+    let fill = fill_table(&real);
+    let q: [usize; NTRIPLES] = std::array::from_fn(|i| i);
+    assert_eq!(fill[index(&[0; NTRIPLES], &q)], Fill::Open);
+    for s in 0..1 << NTRIPLES {
+        let f: Vec<i8> = (0..NTRIPLES).map(|i| if (s >> (NTRIPLES-1-i)) & 1 == 1 { 1 } else { -1 }).collect();
+        let expected = if real.contains(&s) { Fill::Forced(s) } else { Fill::Invalid };
+        assert_eq!(fill[index(&f, &q)], expected);
+    }
+}
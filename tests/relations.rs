@@ -0,0 +1,25 @@
+#![allow(clippy::needless_return)]
+
+// Lazy generates the same relations as Materialized.
+
+use he::comb::ntriples;
+use he::relations::{Lazy, Materialized, Relations, all_relations};
+
+fn sorted(rels: &[(i8,usize)], k: usize) -> Vec<Vec<(i8,usize)>> {
+    let mut v: Vec<Vec<(i8,usize)>> = rels.chunks_exact(k-2).map(|r| r.to_vec()).collect();
+    v.sort();
+    return v;
+}
+
+#[test]
+fn lazy_relations() {
+    for (k,n) in [(4,7), (5,8), (6,9), (7,10)] {
+        let (lazy, materialized) = (Lazy { k, n }, Materialized::new(n, all_relations(k, n)));
+        let (mut b1, mut b2) = (Vec::new(), Vec::new());
+        for t in 0..ntriples(n) {
+            let (l, m) = (sorted(lazy.involving(t, &mut b1), k), sorted(materialized.involving(t, &mut b2), k));
+            assert!(!m.is_empty());
+            assert_eq!(l, m, "k: {} n: {} triple: {}", k, n, t);
+        }
+    }
+}
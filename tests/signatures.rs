@@ -0,0 +1,33 @@
+#![allow(clippy::needless_return)]
+
+// The search on 17 points for some of the cheapest signatures of results.txt
+// (524289 takes half an hour), and the total for hexagons on 8 points, so that
+// changes to the propagation cannot change results unnoticed.
+
+use std::fs;
+use he::solver::{Limits, Run, try_signature};
+use he::szekeres6;
+use he::tables::Tables;
+
+// The number of leaves passing the one-bit-check in results.txt.
+fn stage1(v: u64) -> u64 {
+    let text = fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/results.txt")).unwrap();
+    let line = text.lines().find(|l| l.split_whitespace().next() == Some(&v.to_string())).unwrap();
+    return line.split_whitespace().nth(1).unwrap().parse().unwrap();
+}
+
+#[test]
+fn cheap_signatures() {
+    let t = Tables::new(6, 17);
+    for v in [525331, 972634] {
+        let mut run = Run::new(v, Limits::default());
+        try_signature(&t, &mut run);
+        assert_eq!(run.passed[0], stage1(v), "signature {}", v);
+        assert_eq!(run.survivors(), 0, "signature {}", v);
+    }
+}
+
+#[test]
+fn hexagons_on_8_points() {
+    assert_eq!(szekeres6::total(&Tables::new(6, 8)), 76995);
+}
@@ -0,0 +1,66 @@
+#![allow(clippy::needless_return)]
+
+// The stage 1 files of pentagons on 8 points hold the leaves passing the
+// one-bit-check, also when the run is stopped and resumed several times, and
+// stages counts on them what the search does.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use he::solver::{Limits, Run, try_signature};
+use he::survivors::load;
+use he::szekeres6::signatures;
+use he::tables::Tables;
+
+fn he(dir: &Path, args: &[&str]) -> String {
+    let out = Command::new(env!("CARGO_BIN_EXE_he")).args(args).args(["--k", "5", "--n", "8", "--progress", "0"])
+        .current_dir(dir).output().unwrap();
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    return String::from_utf8(out.stdout).unwrap();
+}
+
+#[test]
+fn stage1_files() {
+    let dir = std::env::temp_dir().join(format!("he-survivors-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let t = Tables::new(5, 8);
+    let runs: Vec<Run> = signatures(&t).into_iter().map(|v| {
+        let mut run = Run::new(v, Limits::default());
+        try_signature(&t, &mut run);
+        run
+    }).collect();
+    he(&dir, &["run", "--dump", "full"]);
+    let mut files = Vec::new();
+    for run in runs.iter() {
+        let file = format!("full/{}.s1", run.v);
+        let (k, n, v, recs) = load(&dir.join(&file)).unwrap();
+        assert_eq!((k, n, v, recs.len() as u64), (5, 8, run.v, run.passed[0]));
+        files.push(file);
+    }
+    let mut args = vec!["stages"];
+    args.extend(files.iter().map(|f| f.as_str()));
+    let counts = he(&dir, &args);
+    for (line, run) in counts.lines().zip(runs.iter()) {
+        assert_eq!(line.split_whitespace().map(|w| w.parse().unwrap()).collect::<Vec<u64>>(),
+                   vec![run.v, run.passed[0], run.passed[1], run.passed[2]]);
+    }
+    assert_eq!(counts.lines().count(), runs.len());
+    // the signature with the most leaves, a few nodes at a time.
+    let run = runs.iter().max_by_key(|r| r.passed[0]).unwrap();
+    assert!(run.passed[0] > 0);
+    let mut c = Run::new(run.v, Limits::default()).checkpoint();
+    let mut slices = 0;
+    loop {
+        let line = he(&dir, &["run", "--resume", &c, "--max-nodes", "2", "--dump", "sliced"]);
+        match line.split_once("checkpoint: ") {
+            Some((_, rest)) => c = rest.trim().to_string(),
+            None => break,
+        }
+        slices += 1;
+    }
+    assert!(slices > 1);
+    let name = format!("{}.s1", run.v);
+    assert_eq!(load(&dir.join("sliced").join(&name)).unwrap(), load(&dir.join("full").join(&name)).unwrap());
+    fs::remove_dir_all(&dir).unwrap();
+}